@@ -1,17 +1,24 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_json_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+    to_json_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
 };
-use cw_ownable::update_ownership;
+use semver::Version;
 
 use crate::error::ContractError;
 use crate::execute::*;
 use crate::helpers::*;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::migrations::migrate_state;
+use crate::msg::{
+    ExecuteMsg, InstantiateMsg, Logo, LogoInfo, MarketingInfoResponse, MigrateMsg, QueryMsg,
+};
+use crate::query::*;
+use crate::roles::{grant_role, Role};
 use crate::state::*;
 
-const MAX_FEE_RATE: u128 = 3; // Maximum fee rate as a percentage
+// Version info for migration
+pub const CONTRACT_NAME: &str = concat!("crates.io:", env!("CARGO_PKG_NAME"));
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -25,17 +32,25 @@ pub fn instantiate(
     cw_utils::nonpayable(&info)?;
 
     let owner = validate_addr(deps.as_ref(), &msg.owner)?;
-    let fee_collector = deps.api.addr_validate(&msg.fee_collector)?;
+    let fee_recipients = validate_fee_recipients(deps.as_ref(), msg.fee_recipients)?;
 
-    if msg.fee_rate > MAX_FEE_RATE {
-        return Err(ContractError::InvalidFees {
-            allowed: MAX_FEE_RATE,
-            passed: msg.fee_rate,
-        });
+    // A fee can never be more than the transferred amount
+    let max_fee_rate = msg.max_fee_rate.unwrap_or(DEFAULT_MAX_FEE_RATE);
+    validate_fee_rate(max_fee_rate, Decimal::one())?;
+
+    let fee_rate = msg.fee_rate.to_decimal();
+    validate_fee_rate(fee_rate, max_fee_rate)?;
+    let fee_tiers = validate_fee_tiers(msg.fee_tiers, max_fee_rate)?;
+    validate_fee_bounds(msg.min_fee, msg.max_fee)?;
+    let mint_fee_rate = msg.mint_fee_rate.map(|rate| rate.to_decimal());
+    let burn_fee_rate = msg.burn_fee_rate.map(|rate| rate.to_decimal());
+    for rate in mint_fee_rate.iter().chain(&burn_fee_rate) {
+        validate_fee_rate(*rate, max_fee_rate)?;
     }
 
-    // Convert fee rate to decimals
-    let fee_rate_decimal = Decimal::from_ratio(msg.fee_rate, 100u128);
+    let fee_change_delay =
+        validate_delay(msg.fee_change_delay.unwrap_or(DEFAULT_FEE_CHANGE_DELAY))?;
+    let timelock_delay = validate_delay(msg.timelock_delay.unwrap_or(DEFAULT_TIMELOCK_DELAY))?;
 
     let token_info = TokenInfo {
         name: msg.name,
@@ -43,19 +58,79 @@ pub fn instantiate(
         decimals: msg.decimals,
         circulating_supply: msg.initial_supply,
         max_supply: msg.max_supply,
-        owner,
-        fee_collector,
-        fee_rate: fee_rate_decimal,
+        fee_recipients,
+        fee_rate,
+        fee_tiers,
+        max_fee_rate,
+        fee_rounding: msg.fee_rounding.unwrap_or_default(),
+        mint_fee_rate,
+        burn_fee_rate,
+        min_fee: msg.min_fee,
+        max_fee: msg.max_fee,
+        fee_change_delay,
+        timelock_delay,
     };
 
-    if &token_info.max_supply < &token_info.circulating_supply {
+    if token_info.max_supply < token_info.circulating_supply {
         return Err(ContractError::InvalidSupply {});
     }
 
     TOKEN_INFO.save(deps.storage, &token_info)?;
 
+    // The owner holds every role that is not handed to someone else
+    let minter = match msg.mint {
+        Some(mint) => {
+            let minter = validate_addr(deps.as_ref(), &mint.minter)?;
+            if let Some(cap) = mint.cap {
+                if cap < token_info.circulating_supply {
+                    return Err(ContractError::InvalidSupply {});
+                }
+                MINTER_CAPS.save(deps.storage, &minter, &cap)?;
+            }
+            minter
+        }
+        None => owner.clone(),
+    };
+    grant_role(deps.storage, Role::Minter, &minter)?;
+    MINTER.save(deps.storage, &minter)?;
+
+    let pauser = match msg.pauser {
+        Some(pauser) => validate_addr(deps.as_ref(), &pauser)?,
+        None => owner.clone(),
+    };
+    grant_role(deps.storage, Role::Pauser, &pauser)?;
+    grant_role(deps.storage, Role::FeeManager, &owner)?;
+    grant_role(deps.storage, Role::ComplianceOfficer, &owner)?;
+
+    if let Some(marketing) = msg.marketing {
+        let logo = match marketing.logo {
+            Some(logo) => {
+                verify_logo(&logo)?;
+                LOGO.save(deps.storage, &logo)?;
+                Some(match logo {
+                    Logo::Url(url) => LogoInfo::Url(url),
+                    Logo::Embedded(_) => LogoInfo::Embedded,
+                })
+            }
+            None => None,
+        };
+
+        let marketing_info = MarketingInfoResponse {
+            project: marketing.project,
+            description: marketing.description,
+            marketing: marketing
+                .marketing
+                .map(|addr| validate_addr(deps.as_ref(), &addr))
+                .transpose()?,
+            logo,
+        };
+        MARKETING_INFO.save(deps.storage, &marketing_info)?;
+    }
+
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
     cw_ownable::initialize_owner(deps.storage, deps.api, Some(msg.owner.as_str()))?;
-    BALANCES.save(deps.storage, &token_info.owner, &msg.initial_supply)?;
+    BALANCES.save(deps.storage, &owner, &msg.initial_supply)?;
     Ok(Response::new()
         .add_attribute("method", "instantiate")
         .add_attribute("owner", msg.owner)
@@ -70,52 +145,203 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    
+    // Owner actions only take effect through the timelock queue
+    if requires_timelock(deps.storage, &info.sender, &msg)? {
+        return Err(ContractError::TimelockRequired {});
+    }
+    dispatch(deps, env, info, msg)
+}
+
+/// Routes `msg` to its handler, operations leaving the timelock queue come in here
+pub fn dispatch(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Transfer { recipient, amount } => {
-            execute_transfer(deps, info, recipient, amount)
+            execute_transfer(deps, env, info, recipient, amount)
         }
+        ExecuteMsg::Send {
+            contract,
+            amount,
+            msg,
+        } => execute_send(deps, env, info, contract, amount, msg),
         ExecuteMsg::TransferFrom {
             owner,
             recipient,
             amount,
-        } => execute_transfer_from(deps, info, owner, recipient, amount),
+        } => execute_transfer_from(deps, env, info, owner, recipient, amount),
+        ExecuteMsg::SendFrom {
+            owner,
+            contract,
+            amount,
+            msg,
+        } => execute_send_from(deps, env, info, owner, contract, amount, msg),
 
         ExecuteMsg::Mint { recipient, amount } => execute_mint(deps, info, recipient, amount),
         ExecuteMsg::Burn { amount } => execute_burn(deps, info, amount),
+        ExecuteMsg::UpdateMarketing {
+            project,
+            description,
+            marketing,
+        } => execute_update_marketing(deps, info, project, description, marketing),
+        ExecuteMsg::UploadLogo(logo) => execute_upload_logo(deps, info, logo),
+        ExecuteMsg::ProposeFeeChange {
+            fee_rate,
+            fee_recipients,
+            fee_tiers,
+        } => execute_propose_fee_change(deps, env, info, fee_rate, fee_recipients, fee_tiers),
+        ExecuteMsg::ApplyFeeChange {} => execute_apply_fee_change(deps, env, info),
+        ExecuteMsg::ClaimFees { recipient } => execute_claim_fees(deps, info, recipient),
+        ExecuteMsg::AddFeeExemption { address, kind } => {
+            execute_add_fee_exemption(deps, info, address, kind)
+        }
+        ExecuteMsg::RemoveFeeExemption { address } => {
+            execute_remove_fee_exemption(deps, info, address)
+        }
+        ExecuteMsg::Freeze { address, reason } => execute_freeze(deps, env, info, address, reason),
+        ExecuteMsg::Unfreeze { address } => execute_unfreeze(deps, info, address),
+        ExecuteMsg::GrantRole { role, address } => execute_grant_role(deps, info, role, address),
+        ExecuteMsg::RevokeRole { role, address } => execute_revoke_role(deps, info, role, address),
+        ExecuteMsg::RenounceRole { role } => execute_renounce_role(deps, info, role),
+        ExecuteMsg::Pause { operations } => execute_set_paused(deps, info, operations, true),
+        ExecuteMsg::Unpause { operations } => execute_set_paused(deps, info, operations, false),
+        ExecuteMsg::SetRateLimit { address, limit } => {
+            execute_set_rate_limit(deps, info, address, limit)
+        }
+        ExecuteMsg::ScheduleOperation { msg } => execute_schedule_operation(deps, env, info, *msg),
+        ExecuteMsg::ExecuteOperation { id } => execute_scheduled_operation(deps, env, info, id),
+        ExecuteMsg::CancelOperation { id } => execute_cancel_operation(deps, info, id),
+        ExecuteMsg::UpdateMinter { new_minter, cap } => {
+            execute_update_minter(deps, info, new_minter, cap)
+        }
+        ExecuteMsg::SetMinterCap { minter, cap } => execute_set_minter_cap(deps, info, minter, cap),
+        ExecuteMsg::BurnFrom { owner, amount } => {
+            execute_burn_from(deps, env, info, owner, amount)
+        }
 
         ExecuteMsg::IncreaseAllowance {
-            owner,
+            owner: _,
             spender,
             amount,
-        } => execute_increase_allowance(deps, info, spender, amount),
+            expires,
+        } => execute_increase_allowance(deps, env, info, spender, amount, expires),
 
         ExecuteMsg::DecreaseAllowance {
-            owner,
+            owner: _,
             spender,
             amount,
-        } => execute_decrease_allowance(deps, info, spender, amount),
+            expires,
+        } => execute_decrease_allowance(deps, env, info, spender, amount, expires),
 
-        ExecuteMsg::UpdateOwnership(action) => {
-            // Handle the ownership update action
-            update_ownership(deps, &env.block, &info.sender, action)?;
-            Ok(Response::new().add_attribute("action", "update_ownership"))
-        }
+        ExecuteMsg::UpdateOwnership(action) => execute_update_ownership(deps, env, info, action),
         
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::TokenInfo {} => to_json_binary(&TOKEN_INFO.load(deps.storage)?),
-        QueryMsg::Balance { address } => {
-            let addr =
-                validate_addr(deps, &address).map_err(|e| StdError::generic_err(e.to_string()))?;
-
-            let balance = BALANCES.may_load(deps.storage, &addr)?.unwrap_or(0);
-            to_json_binary(&balance)
+        QueryMsg::TokenInfo {} => to_json_binary(&query_token_info(deps)?),
+        QueryMsg::Balance { address } => to_json_binary(&query_balance(deps, address)?),
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::PendingFeeChange {} => to_json_binary(&query_pending_fee_change(deps)?),
+        QueryMsg::FeeSchedule {} => to_json_binary(&query_fee_schedule(deps)?),
+        QueryMsg::SimulateTransfer {
+            sender,
+            recipient,
+            amount,
+        } => to_json_binary(&query_simulate_transfer(deps, &env.block, sender, recipient, amount)?),
+        QueryMsg::AccruedFees { address } => to_json_binary(&query_accrued_fees(deps, address)?),
+        QueryMsg::FeeExemptions { start_after, limit } => {
+            to_json_binary(&query_fee_exemptions(deps, start_after, limit)?)
         }
+        QueryMsg::FrozenAccounts { start_after, limit } => {
+            to_json_binary(&query_frozen_accounts(deps, start_after, limit)?)
+        }
+        QueryMsg::HasRole { role, address } => {
+            to_json_binary(&query_has_role(deps, role, address)?)
+        }
+        QueryMsg::RoleMembers {
+            role,
+            start_after,
+            limit,
+        } => to_json_binary(&query_role_members(deps, role, start_after, limit)?),
+        QueryMsg::RateLimit { address } => {
+            to_json_binary(&query_rate_limit(deps, &env.block, address)?)
+        }
+        QueryMsg::ScheduledOperation { id } => {
+            to_json_binary(&query_scheduled_operation(deps, id)?)
+        }
+        QueryMsg::ScheduledOperations { start_after, limit } => {
+            to_json_binary(&query_scheduled_operations(deps, start_after, limit)?)
+        }
+        QueryMsg::PauseInfo {} => to_json_binary(&query_pause_info(deps)?),
+        QueryMsg::Minter {} => to_json_binary(&query_minter(deps)?),
+        QueryMsg::MarketingInfo {} => to_json_binary(&query_marketing_info(deps)?),
+        QueryMsg::DownloadLogo {} => to_json_binary(&query_download_logo(deps)?),
+        QueryMsg::Allowance { owner, spender } => {
+            to_json_binary(&query_allowance(deps, owner, spender)?)
+        }
+        QueryMsg::AllAllowances {
+            owner,
+            start_after,
+            limit,
+        } => to_json_binary(&query_all_allowances(deps, owner, start_after, limit)?),
+        QueryMsg::AllSpenderAllowances {
+            spender,
+            start_after,
+            limit,
+        } => to_json_binary(&query_all_spender_allowances(
+            deps,
+            spender,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::AllAccounts {
+            start_after,
+            limit,
+            include_zero_balances,
+        } => to_json_binary(&query_all_accounts(
+            deps,
+            start_after,
+            limit,
+            include_zero_balances.unwrap_or(false),
+        )?),
     }
 }
 
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    // Instances deployed before cw2 was wired in have no version stored
+    let previous_version = match cw2::CONTRACT.may_load(deps.storage)? {
+        Some(stored) => {
+            if stored.contract != CONTRACT_NAME {
+                return Err(ContractError::CannotMigrate {
+                    previous_contract: stored.contract,
+                });
+            }
+            stored.version.parse::<Version>()?
+        }
+        None => Version::new(0, 1, 0),
+    };
+
+    let new_version = CONTRACT_VERSION.parse::<Version>()?;
+    if previous_version > new_version {
+        return Err(ContractError::CannotMigrateVersion {
+            previous_version: previous_version.to_string(),
+            new_version: new_version.to_string(),
+        });
+    }
+
+    migrate_state(deps.storage, &previous_version)?;
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("previous_version", previous_version.to_string())
+        .add_attribute("new_version", new_version.to_string()))
+}
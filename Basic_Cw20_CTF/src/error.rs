@@ -1,5 +1,7 @@
-use cosmwasm_std::StdError;
-use cw_utils::PaymentError;
+use cosmwasm_std::{Decimal, OverflowError, StdError, Uint128};
+use cw_utils::{Duration, Expiration, PaymentError};
+
+use crate::state::Operation;
 use thiserror::Error;
 use cw_ownable::OwnershipError;
 
@@ -8,14 +10,29 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
-    #[error("Unauthorized: Only the owner can perform this action")]
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("Unauthorized: Sender lacks the role or permission for this action")]
     Unauthorized {},
 
     #[error("Insufficient funds: You tried to transfer {amount} but only have {balance}")]
-    InsufficientFunds { amount: u128, balance: u128 },
+    InsufficientFunds { amount: Uint128, balance: Uint128 },
 
     #[error("Max supply reached: Cannot mint more than the maximum supply of {max_supply}")]
-    MaxSupplyReached { max_supply: u128 },
+    MaxSupplyReached { max_supply: Uint128 },
+
+    #[error("Minting cannot exceed the cap of {cap}")]
+    CannotExceedCap { cap: Uint128 },
+
+    #[error("Logo binary data exceeds 5KB limit")]
+    LogoTooBig {},
+
+    #[error("Invalid xml preamble for SVG")]
+    InvalidXmlPreamble {},
+
+    #[error("Invalid png header")]
+    InvalidPngHeader {},
 
     #[error("Invalid recipient address")]
     InvalidRecipient {},
@@ -24,9 +41,15 @@ pub enum ContractError {
     InvalidAddress { addr: String },
 
     #[error("Not Enough Allowance")]
-    InsufficientAllowance { allowance: u128, required: u128 },
+    InsufficientAllowance { allowance: Uint128, required: Uint128 },
+
+    #[error("Allowance is expired")]
+    Expired {},
 
-    #[error("Cannot Provide Allowance more than the Upper Limit of u128")]
+    #[error("Invalid expiration: Expiration is already in the past")]
+    InvalidExpiration {},
+
+    #[error("Cannot Provide Allowance more than the Upper Limit of Uint128")]
     Overflow {},
 
     #[error("Invalid amount: Amount must be greater than zero")]
@@ -38,8 +61,68 @@ pub enum ContractError {
     #[error("Operation failed due to an unknown error")]
     UnknownError {},
 
-    #[error("Fees cannot be more than 3%")]
-    InvalidFees { allowed: u128, passed: u128 },
+    #[error("Invalid fees: Fee rate {passed} exceeds the maximum of {allowed}")]
+    InvalidFees { allowed: Decimal, passed: Decimal },
+
+    #[error("Minimum fee {min_fee} exceeds the maximum fee {max_fee}")]
+    InvalidFeeBounds { min_fee: Uint128, max_fee: Uint128 },
+
+    #[error("Amount {amount} is below the minimum fee of {min_fee}")]
+    BelowMinFee { amount: Uint128, min_fee: Uint128 },
+
+    #[error("Cannot migrate from a different contract: {previous_contract}")]
+    CannotMigrate { previous_contract: String },
+
+    #[error("Cannot migrate from newer version {previous_version} to {new_version}")]
+    CannotMigrateVersion {
+        previous_version: String,
+        new_version: String,
+    },
+
+    #[error("Semver parsing error: {0}")]
+    SemVer(String),
+
+    #[error("Invalid fee recipients: {reason}")]
+    InvalidFeeRecipients { reason: String },
+
+    #[error("Account is frozen: {address}")]
+    AccountFrozen { address: String },
+
+    #[error("Operation is paused: {operation}")]
+    Paused { operation: Operation },
+
+    #[error("Invalid fee tiers: {reason}")]
+    InvalidFeeTiers { reason: String },
+
+    #[error("No accrued fees to claim")]
+    NoAccruedFees {},
+
+    #[error("No fee change has been proposed")]
+    NoPendingFeeChange {},
+
+    #[error("Fee change cannot be applied before {ready_at}")]
+    FeeChangeNotReady { ready_at: Expiration },
+
+    #[error("Scheduled operation {id} does not exist")]
+    OperationNotFound { id: u64 },
+
+    #[error("Scheduled operation {id} cannot be executed before {ready_at}")]
+    OperationNotReady { id: u64, ready_at: Expiration },
+
+    #[error("Owner actions must be scheduled with ScheduleOperation")]
+    TimelockRequired {},
+
+    #[error("Timelock messages cannot be scheduled themselves")]
+    InvalidScheduledOperation {},
+
+    #[error("Transfer exceeds the outflow limit, {remaining} left in the current window")]
+    RateLimitExceeded { remaining: Uint128 },
+
+    #[error("Rate limit window must be between one second and one year")]
+    InvalidRateLimit {},
+
+    #[error("Delay {delay} is longer than about a year")]
+    InvalidDelay { delay: Duration },
 
     #[error("{0}")]
     PaymentError(#[from] PaymentError),
@@ -47,3 +130,9 @@ pub enum ContractError {
     #[error("Ownership Error: {0}")]
     Ownership(#[from] OwnershipError),
 }
+
+impl From<semver::Error> for ContractError {
+    fn from(err: semver::Error) -> Self {
+        Self::SemVer(err.to_string())
+    }
+}
@@ -1,166 +1,180 @@
+use crate::contract::dispatch;
 use crate::error::ContractError;
-use crate::helpers::{calculate_fee, decrease_allowance, increase_allowance, validate_addr};
-use crate::state::{ALLOWANCES, BALANCES, TOKEN_INFO};
-use cosmwasm_std::{DepsMut, MessageInfo, Response, Decimal};
+use crate::helpers::{
+    assert_not_frozen, assert_not_paused, assert_transfer_allowed, burn_tokens,
+    calculate_supply_fee, decrease_allowance, distribute_fee, fee_shares, increase_allowance,
+    join_fee_recipients, record_outflow, spend_allowance, transfer_with_fee, validate_addr,
+    validate_fee_rate, validate_fee_recipients, validate_fee_tiers, verify_logo,
+    MAX_DURATION_SECONDS,
+};
+use crate::msg::{Cw20ReceiveMsg, ExecuteMsg, FeeRate, FeeRecipient, FeeTier, Logo, LogoInfo};
+use crate::roles::{assert_role, grant_role, minter_data, revoke_role, transfer_roles, Role};
+use crate::state::{
+    ExemptionKind, FreezeInfo, Operation, PendingFeeChange, RateLimit, ScheduledOperation,
+    ACCRUED_FEES, BALANCES, FEE_EXEMPT, FROZEN, GLOBAL_RATE_LIMIT, LOGO, MARKETING_INFO, MINTER,
+    MINTER_CAPS, NEXT_OPERATION_ID, PAUSED, PENDING_FEE_CHANGE, RATE_LIMITS, SCHEDULED_OPERATIONS,
+    TOKEN_INFO,
+};
+use cosmwasm_std::{
+    from_json, to_json_binary, to_json_string, Binary, DepsMut, Env, Event, MessageInfo, Response,
+    Uint128,
+};
+use cw_utils::Expiration;
 
 pub fn execute_transfer(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     recipient: String,
-    amount: u128,
+    amount: Uint128,
 ) -> Result<Response, ContractError> {
-    // Load the token Info
-    let token_info = TOKEN_INFO.load(deps.storage)?;
-
     let sender_addr = info.sender;
     let recipient_addr = validate_addr(deps.as_ref(), &recipient)?;
+    // The same checks `SimulateTransfer` runs
+    assert_transfer_allowed(deps.storage, &env.block, &sender_addr, &recipient_addr, amount)?;
+    record_outflow(deps.storage, &env.block, &sender_addr, amount)?;
 
-    let sender_balance = BALANCES.load(deps.storage, &sender_addr)?;
+    //fee deduction and balance updates
+    let (_, fee) = transfer_with_fee(deps, &sender_addr, &recipient_addr, amount)?;
 
-    let recipient_balance = BALANCES
-        .may_load(deps.storage, &recipient_addr)?
-        .unwrap_or(0);
-
-    //fee deduction
-    let fee_percent = token_info.fee_rate;
-    let (net_amount, fee) = calculate_fee(amount, fee_percent)?;
+    Ok(Response::new()
+        .add_attribute("method", "transfer")
+        .add_attribute("sender", sender_addr)
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("fee", fee.to_string()))
+}
 
-    // Ensure the sender has enough balance to cover amount + fee
-    if sender_balance < amount {
-        return Err(ContractError::InsufficientFunds {
-            amount: amount,
-            balance: sender_balance,
-        });
+pub fn execute_send(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: String,
+    amount: Uint128,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Transfer)?;
+    cw_utils::nonpayable(&info)?;
+    if amount.is_zero() {
+        return Err(ContractError::InvalidAmount {});
     }
-    // Update fee collector's balance
-    let fee_collector_balance = BALANCES
-        .may_load(deps.storage, &token_info.fee_collector)?
-        .unwrap_or(0);
 
-    let new_fee_collector_balance = fee_collector_balance
-        .checked_add(fee)
-        .ok_or(ContractError::Overflow {})?;
+    let sender_addr = info.sender;
+    let contract_addr = validate_addr(deps.as_ref(), &contract)?;
+    record_outflow(deps.storage, &env.block, &sender_addr, amount)?;
 
-    // Update all the balances
-    BALANCES.save(
-        deps.storage,
-        &sender_addr,
-        &(sender_balance.saturating_sub(amount)),
-    )?;
-    BALANCES.save(
-        deps.storage,
-        &recipient_addr,
-        &(recipient_balance.saturating_add(net_amount)),
-    )?;
-    BALANCES.save(
-        deps.storage,
-        &token_info.fee_collector,
-        &new_fee_collector_balance,
-    )?;
+    // Same fee deduction as a plain transfer, the receiver is told the net amount
+    let (net_amount, fee) = transfer_with_fee(deps, &sender_addr, &contract_addr, amount)?;
+
+    let receive_msg = Cw20ReceiveMsg {
+        sender: sender_addr.to_string(),
+        amount: net_amount,
+        msg,
+    }
+    .into_cosmos_msg(&contract_addr)?;
 
     Ok(Response::new()
-        .add_attribute("method", "transfer_from")
+        .add_message(receive_msg)
+        .add_attribute("method", "send")
         .add_attribute("sender", sender_addr)
-        .add_attribute("recipient", recipient)
+        .add_attribute("contract", contract)
         .add_attribute("amount", amount.to_string())
         .add_attribute("fee", fee.to_string()))
 }
 
 pub fn execute_transfer_from(
-    deps: DepsMut,
+    mut deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     owner: String,
     recipient: String,
-    amount: u128,
+    amount: Uint128,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Transfer)?;
+    if amount.is_zero() {
+        return Err(ContractError::InvalidAmount {});
+    }
     //validating addresses
     let owner_addr = validate_addr(deps.as_ref(), &owner)?;
     let recipient_addr = validate_addr(deps.as_ref(), &recipient)?;
 
-    //Validating Allowances
+    //Consuming the spender's allowance, fails if it is insufficient
     let spender = info.sender;
-    let allowance = ALLOWANCES
-        .may_load(deps.storage, (&owner_addr, &spender))?
-        .unwrap_or(0);
-
-    if allowance < amount {
-        deps.api.debug("Allowance insufficient, resetting to 0.");
-        ALLOWANCES.save(
-            deps.storage,
-            (&owner_addr, &spender),
-            &0, 
-        )?;
-    }
+    // A frozen account cannot move funds it was approved for either
+    assert_not_frozen(deps.storage, &spender)?;
+    spend_allowance(deps.branch(), &env.block, &owner_addr, &spender, amount)?;
+    // The owner's limit applies, whoever moves the tokens
+    record_outflow(deps.storage, &env.block, &owner_addr, amount)?;
 
+    // Same fee path as a plain transfer
+    let (_, fee) = transfer_with_fee(deps, &owner_addr, &recipient_addr, amount)?;
 
-    // Load token info for fee calculation
-    let token_info = TOKEN_INFO.load(deps.storage)?;
+    Ok(Response::new()
+        .add_attribute("method", "transfer_from")
+        .add_attribute("owner", owner)
+        .add_attribute("spender", spender.to_string())
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("fee", fee.to_string()))
+}
 
-    // Load Balances
-    let owner_bal = BALANCES.load(deps.storage, &owner_addr)?;
+pub fn execute_send_from(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    contract: String,
+    amount: Uint128,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Transfer)?;
+    cw_utils::nonpayable(&info)?;
+    if amount.is_zero() {
+        return Err(ContractError::InvalidAmount {});
+    }
 
-    let recipient_bal = BALANCES
-        .may_load(deps.storage, &recipient_addr)?
-        .unwrap_or(0);
+    let owner_addr = validate_addr(deps.as_ref(), &owner)?;
+    let contract_addr = validate_addr(deps.as_ref(), &contract)?;
 
-    let fee_collector_balance = BALANCES
-        .may_load(deps.storage, &token_info.fee_collector)?
-        .unwrap_or(0);
+    let spender = info.sender;
+    assert_not_frozen(deps.storage, &spender)?;
+    spend_allowance(deps.branch(), &env.block, &owner_addr, &spender, amount)?;
+    record_outflow(deps.storage, &env.block, &owner_addr, amount)?;
 
-    // Calculate the fee and net amount for transfer
-    let fee_percent = if recipient == token_info.fee_collector.to_string() {
-        Decimal::zero() // No fees for transfers to the fee collector
-    } else {
-        token_info.fee_rate
-    };
-    let (net_amount, fee) = calculate_fee(amount, fee_percent)?;
+    let (net_amount, fee) = transfer_with_fee(deps, &owner_addr, &contract_addr, amount)?;
 
-    // Ensure the owner's balance is sufficient to cover the transfer and fee
-    if owner_bal < amount {
-        return Err(ContractError::InsufficientFunds {
-            amount: amount,
-            balance: owner_bal,
-        });
+    // The receiver sees the owner as the sender of the tokens
+    let receive_msg = Cw20ReceiveMsg {
+        sender: owner_addr.to_string(),
+        amount: net_amount,
+        msg,
     }
-
-    BALANCES.save(
-        deps.storage,
-        &owner_addr,
-        &(owner_bal.saturating_sub(amount)),
-    )?;
-
-    BALANCES.save(
-        deps.storage,
-        &recipient_addr,
-        &(recipient_bal.saturating_add(net_amount)),
-    )?;
-
-    BALANCES.save(
-        deps.storage,
-        &token_info.fee_collector,
-        &(fee_collector_balance.saturating_add(fee)),
-    )?;
+    .into_cosmos_msg(&contract_addr)?;
 
     Ok(Response::new()
-        .add_attribute("method", "transfer_from")
+        .add_message(receive_msg)
+        .add_attribute("method", "send_from")
         .add_attribute("owner", owner)
-        .add_attribute("spender", spender.to_string())
-        .add_attribute("recipient", recipient)
+        .add_attribute("spender", spender)
+        .add_attribute("contract", contract)
         .add_attribute("amount", amount.to_string())
         .add_attribute("fee", fee.to_string()))
 }
 
 pub fn execute_increase_allowance(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     spender: String,
-    amount: u128,
+    amount: Uint128,
+    expires: Option<Expiration>,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Allowance)?;
     cw_utils::nonpayable(&info)?;
     let owner = info.sender;
     let spender_addr = validate_addr(deps.as_ref(), &spender)?;
 
-    increase_allowance(deps, &owner, &spender_addr, amount)?;
+    increase_allowance(deps, &env.block, &owner, &spender_addr, amount, expires)?;
 
     Ok(Response::new()
         .add_attribute("method", "increase_allowance")
@@ -171,14 +185,17 @@ pub fn execute_increase_allowance(
 
 pub fn execute_decrease_allowance(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     spender: String,
-    amount: u128,
+    amount: Uint128,
+    expires: Option<Expiration>,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Allowance)?;
     let owner = info.sender;
     let spender_addr = validate_addr(deps.as_ref(), &spender)?;
 
-    decrease_allowance(deps, &owner, &spender_addr, amount)?;
+    decrease_allowance(deps, &env.block, &owner, &spender_addr, amount, expires)?;
 
     Ok(Response::new()
         .add_attribute("method", "decrease_allowance")
@@ -191,74 +208,606 @@ pub fn execute_mint(
     deps: DepsMut,
     info: MessageInfo,
     recipient: String,
-    amount: u128,
+    amount: Uint128,
 ) -> Result<Response, ContractError> {
-
+    assert_not_paused(deps.storage, Operation::Mint)?;
     cw_utils::nonpayable(&info)?;
-    // Only Owner can Mint tokens til the max supply is reached
-    let sender_addr = info.sender;
-    let _ = cw_ownable::assert_owner(deps.storage, &sender_addr);
-    if amount == 0 {
+    // Only minters can Mint tokens til their cap and the max supply are reached
+    let minter = minter_data(deps.storage, &info.sender)?.ok_or(ContractError::Unauthorized {})?;
+    if amount.is_zero() {
         return Err(ContractError::InvalidAmount {});
     }
 
     let recipient_addr = validate_addr(deps.as_ref(), &recipient)?;
+    assert_not_frozen(deps.storage, &recipient_addr)?;
     let mut token_info = TOKEN_INFO.load(deps.storage)?;
 
-    if token_info.circulating_supply + amount > token_info.max_supply {
+    let new_supply = token_info.circulating_supply.checked_add(amount)?;
+    if new_supply > token_info.max_supply {
         return Err(ContractError::MaxSupplyReached {
             max_supply: token_info.max_supply,
         });
     }
+    if let Some(cap) = minter.cap {
+        if new_supply > cap {
+            return Err(ContractError::CannotExceedCap { cap });
+        }
+    }
+    // The mint fee is minted as well, so the supply grows by the full amount
+    let (net_amount, fee) =
+        calculate_supply_fee(amount, token_info.mint_fee_rate, token_info.fee_rounding)?;
     let recipient_bal = BALANCES
         .may_load(deps.storage, &recipient_addr)?
-        .unwrap_or(0); //  we use may_load because it returns Option<u128> which is required to use unwrap
-    token_info.circulating_supply += amount;
+        .unwrap_or_default(); //  we use may_load because it returns Option<Uint128> which is required to use unwrap
+    token_info.circulating_supply = new_supply;
 
     TOKEN_INFO.save(deps.storage, &token_info)?;
     BALANCES.save(
         deps.storage,
         &recipient_addr,
-        &(recipient_bal.saturating_add(amount)),
+        &(recipient_bal.checked_add(net_amount)?),
     )?;
+    distribute_fee(deps, &fee_shares(&token_info.fee_recipients, fee))?;
 
     Ok(Response::new()
         .add_attribute("action", "mint")
         .add_attribute("recipient", &recipient)
-        .add_attribute("amount", amount.to_string()))
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("fee", fee.to_string()))
 }
 
-pub fn execute_burn(
+pub fn execute_update_marketing(
     deps: DepsMut,
     info: MessageInfo,
-    amount: u128,
+    project: Option<String>,
+    description: Option<String>,
+    marketing: Option<String>,
 ) -> Result<Response, ContractError> {
-    cw_utils::nonpayable(&info)?;
+    let mut marketing_info = MARKETING_INFO.may_load(deps.storage)?.unwrap_or_default();
+    if marketing_info.marketing.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
 
-    if amount == 0 {
-        return Err(ContractError::InvalidAmount {});
+    // An empty string clears the field, `None` leaves it unchanged
+    match project {
+        Some(project) if project.trim().is_empty() => marketing_info.project = None,
+        Some(project) => marketing_info.project = Some(project),
+        None => (),
     }
-    let burner_addr = info.sender;
-    let burner_bal = BALANCES.load(deps.storage, &burner_addr)?;
+    match description {
+        Some(description) if description.trim().is_empty() => marketing_info.description = None,
+        Some(description) => marketing_info.description = Some(description),
+        None => (),
+    }
+    match marketing {
+        Some(marketing) if marketing.trim().is_empty() => marketing_info.marketing = None,
+        Some(marketing) => {
+            marketing_info.marketing = Some(validate_addr(deps.as_ref(), &marketing)?)
+        }
+        None => (),
+    }
+
+    MARKETING_INFO.save(deps.storage, &marketing_info)?;
+
+    Ok(Response::new().add_attribute("action", "update_marketing"))
+}
 
-    if burner_bal < amount {
-        return Err(ContractError::InsufficientFunds {
-            amount,
-            balance: burner_bal,
+pub fn execute_upload_logo(
+    deps: DepsMut,
+    info: MessageInfo,
+    logo: Logo,
+) -> Result<Response, ContractError> {
+    let mut marketing_info = MARKETING_INFO.may_load(deps.storage)?.unwrap_or_default();
+    if marketing_info.marketing.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    verify_logo(&logo)?;
+    marketing_info.logo = Some(match &logo {
+        Logo::Url(url) => LogoInfo::Url(url.clone()),
+        Logo::Embedded(_) => LogoInfo::Embedded,
+    });
+
+    LOGO.save(deps.storage, &logo)?;
+    MARKETING_INFO.save(deps.storage, &marketing_info)?;
+
+    Ok(Response::new().add_attribute("action", "upload_logo"))
+}
+
+pub fn execute_propose_fee_change(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    fee_rate: FeeRate,
+    fee_recipients: Vec<FeeRecipient>,
+    fee_tiers: Vec<FeeTier>,
+) -> Result<Response, ContractError> {
+    assert_role(deps.storage, Role::FeeManager, &info.sender)?;
+
+    let token_info = TOKEN_INFO.load(deps.storage)?;
+    let fee_rate = fee_rate.to_decimal();
+    validate_fee_rate(fee_rate, token_info.max_fee_rate)?;
+
+    let pending = PendingFeeChange {
+        fee_rate,
+        fee_recipients: validate_fee_recipients(deps.as_ref(), fee_recipients)?,
+        fee_tiers: validate_fee_tiers(fee_tiers, token_info.max_fee_rate)?,
+        ready_at: token_info.fee_change_delay.after(&env.block),
+    };
+    PENDING_FEE_CHANGE.save(deps.storage, &pending)?;
+
+    // Indexers watch this event to warn holders ahead of time
+    let event = Event::new("fee_change_proposed")
+        .add_attribute("current_fee_rate", token_info.fee_rate.to_string())
+        .add_attribute("new_fee_rate", fee_rate.to_string())
+        .add_attribute(
+            "current_fee_recipients",
+            join_fee_recipients(&token_info.fee_recipients),
+        )
+        .add_attribute("new_fee_recipients", join_fee_recipients(&pending.fee_recipients))
+        .add_attribute("new_fee_tiers", pending.fee_tiers.len().to_string())
+        .add_attribute("ready_at", pending.ready_at.to_string());
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("action", "propose_fee_change"))
+}
+
+pub fn execute_apply_fee_change(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    assert_role(deps.storage, Role::FeeManager, &info.sender)?;
+
+    let pending = PENDING_FEE_CHANGE
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingFeeChange {})?;
+    if !pending.ready_at.is_expired(&env.block) {
+        return Err(ContractError::FeeChangeNotReady {
+            ready_at: pending.ready_at,
         });
     }
 
-    BALANCES.save(
-        deps.storage,
-        &burner_addr,
-        &(burner_bal.saturating_sub(amount)),
-    )?;
     let mut token_info = TOKEN_INFO.load(deps.storage)?;
-    token_info.circulating_supply = token_info.circulating_supply.saturating_sub(amount);
+    token_info.fee_rate = pending.fee_rate;
+    token_info.fee_recipients = pending.fee_recipients;
+    token_info.fee_tiers = pending.fee_tiers;
     TOKEN_INFO.save(deps.storage, &token_info)?;
+    PENDING_FEE_CHANGE.remove(deps.storage);
+
+    let event = Event::new("fee_change_applied")
+        .add_attribute("fee_rate", token_info.fee_rate.to_string())
+        .add_attribute("fee_recipients", join_fee_recipients(&token_info.fee_recipients));
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("action", "apply_fee_change"))
+}
+
+pub fn execute_claim_fees(
+    deps: DepsMut,
+    info: MessageInfo,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Transfer)?;
+    cw_utils::nonpayable(&info)?;
+
+    // Only accounts that accrued fees, i.e. fee recipients, have anything to claim
+    let collector = info.sender;
+    let accrued = ACCRUED_FEES
+        .may_load(deps.storage, &collector)?
+        .unwrap_or_default();
+    if accrued.is_zero() {
+        return Err(ContractError::NoAccruedFees {});
+    }
+
+    let recipient_addr = match recipient {
+        Some(recipient) => validate_addr(deps.as_ref(), &recipient)?,
+        None => collector.clone(),
+    };
+    assert_not_frozen(deps.storage, &collector)?;
+    assert_not_frozen(deps.storage, &recipient_addr)?;
+
+    // Accrued fees are already in circulation, so the supply is unchanged
+    ACCRUED_FEES.remove(deps.storage, &collector);
+    let recipient_bal = BALANCES
+        .may_load(deps.storage, &recipient_addr)?
+        .unwrap_or_default();
+    BALANCES.save(deps.storage, &recipient_addr, &recipient_bal.checked_add(accrued)?)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "claim_fees")
+        .add_attribute("collector", collector)
+        .add_attribute("recipient", recipient_addr)
+        .add_attribute("amount", accrued.to_string()))
+}
+
+pub fn execute_add_fee_exemption(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    kind: ExemptionKind,
+) -> Result<Response, ContractError> {
+    assert_role(deps.storage, Role::FeeManager, &info.sender)?;
+
+    let addr = validate_addr(deps.as_ref(), &address)?;
+    FEE_EXEMPT.save(deps.storage, &addr, &kind)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "add_fee_exemption")
+        .add_attribute("address", address)
+        .add_attribute("kind", format!("{kind:?}")))
+}
+
+pub fn execute_remove_fee_exemption(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    assert_role(deps.storage, Role::FeeManager, &info.sender)?;
+
+    let addr = validate_addr(deps.as_ref(), &address)?;
+    FEE_EXEMPT.remove(deps.storage, &addr);
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_fee_exemption")
+        .add_attribute("address", address))
+}
+
+pub fn execute_freeze(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    reason: String,
+) -> Result<Response, ContractError> {
+    assert_role(deps.storage, Role::ComplianceOfficer, &info.sender)?;
+
+    let addr = validate_addr(deps.as_ref(), &address)?;
+    let freeze_info = FreezeInfo {
+        reason,
+        frozen_at: env.block.time,
+    };
+    FROZEN.save(deps.storage, &addr, &freeze_info)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "freeze")
+        .add_attribute("address", address)
+        .add_attribute("reason", freeze_info.reason))
+}
+
+pub fn execute_unfreeze(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    assert_role(deps.storage, Role::ComplianceOfficer, &info.sender)?;
+
+    let addr = validate_addr(deps.as_ref(), &address)?;
+    FROZEN.remove(deps.storage, &addr);
+
+    Ok(Response::new()
+        .add_attribute("action", "unfreeze")
+        .add_attribute("address", address))
+}
+
+pub fn execute_update_minter(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_minter: Option<String>,
+    cap: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let new_minter_addr = new_minter
+        .as_deref()
+        .map(|new_minter| validate_addr(deps.as_ref(), new_minter))
+        .transpose()?;
+
+    // Replaces the cw20 minter only, minters granted with `GrantRole` keep their role
+    if let Some(previous) = MINTER.may_load(deps.storage)? {
+        revoke_role(deps.storage, Role::Minter, &previous);
+    }
+    match &new_minter_addr {
+        Some(new_minter_addr) => {
+            grant_role(deps.storage, Role::Minter, new_minter_addr)?;
+            if let Some(cap) = cap {
+                MINTER_CAPS.save(deps.storage, new_minter_addr, &cap)?;
+            }
+            MINTER.save(deps.storage, new_minter_addr)?;
+        }
+        None => MINTER.remove(deps.storage),
+    }
+    let minter = new_minter.unwrap_or_else(|| "None".to_string());
+
+    Ok(Response::new()
+        .add_attribute("action", "update_minter")
+        .add_attribute("new_minter", minter)
+        .add_attribute("cap", cap.map_or("None".to_string(), |cap| cap.to_string())))
+}
+
+/// Caps or uncaps how far `minter` can grow the circulating supply
+pub fn execute_set_minter_cap(
+    deps: DepsMut,
+    info: MessageInfo,
+    minter: String,
+    cap: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let minter_addr = validate_addr(deps.as_ref(), &minter)?;
+    assert_role(deps.storage, Role::Minter, &minter_addr)?;
+    match cap {
+        Some(cap) => MINTER_CAPS.save(deps.storage, &minter_addr, &cap)?,
+        None => MINTER_CAPS.remove(deps.storage, &minter_addr),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_minter_cap")
+        .add_attribute("minter", minter)
+        .add_attribute("cap", cap.map_or("None".to_string(), |cap| cap.to_string())))
+}
+
+/// Pauses or resumes `operations`, every operation when `None`
+pub fn execute_set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    operations: Option<Vec<Operation>>,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    assert_role(deps.storage, Role::Pauser, &info.sender)?;
+
+    let operations = operations.unwrap_or_else(|| Operation::ALL.to_vec());
+    let mut paused_ops = PAUSED.may_load(deps.storage)?.unwrap_or_default();
+    for operation in &operations {
+        paused_ops.set(*operation, paused);
+    }
+    PAUSED.save(deps.storage, &paused_ops)?;
+
+    let operations = operations
+        .iter()
+        .map(|operation| operation.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    Ok(Response::new()
+        .add_attribute("action", if paused { "pause" } else { "unpause" })
+        .add_attribute("operations", operations))
+}
+
+/// Applies a cw-ownable action, the roles of the owner follow the ownership
+pub fn execute_update_ownership(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action: cw_ownable::Action,
+) -> Result<Response, ContractError> {
+    let previous_owner = cw_ownable::get_ownership(deps.storage)?.owner;
+    let ownership =
+        cw_ownable::update_ownership(deps.branch(), &env.block, &info.sender, action)?;
+
+    // A former owner must not keep minting, pausing or managing fees
+    if let Some(previous_owner) = previous_owner {
+        if ownership.owner.as_ref() != Some(&previous_owner) {
+            transfer_roles(deps.storage, &previous_owner, ownership.owner.as_ref())?;
+        }
+    }
+
+    Ok(Response::new().add_attribute("action", "update_ownership"))
+}
+
+pub fn execute_grant_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let addr = validate_addr(deps.as_ref(), &address)?;
+    grant_role(deps.storage, role, &addr)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "grant_role")
+        .add_attribute("role", role.to_string())
+        .add_attribute("address", address))
+}
+
+pub fn execute_revoke_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let addr = validate_addr(deps.as_ref(), &address)?;
+    revoke_role(deps.storage, role, &addr);
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke_role")
+        .add_attribute("role", role.to_string())
+        .add_attribute("address", address))
+}
+
+pub fn execute_renounce_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+) -> Result<Response, ContractError> {
+    assert_role(deps.storage, role, &info.sender)?;
+    revoke_role(deps.storage, role, &info.sender);
+
+    Ok(Response::new()
+        .add_attribute("action", "renounce_role")
+        .add_attribute("role", role.to_string())
+        .add_attribute("address", info.sender))
+}
+
+/// Sets the outflow limit of `address`, or the global one when `None`.
+/// A `None` limit removes it
+pub fn execute_set_rate_limit(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: Option<String>,
+    limit: Option<RateLimit>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let valid_window = 1..=MAX_DURATION_SECONDS;
+    if limit.as_ref().is_some_and(|limit| !valid_window.contains(&limit.window)) {
+        return Err(ContractError::InvalidRateLimit {});
+    }
+
+    let addr = address
+        .as_deref()
+        .map(|address| validate_addr(deps.as_ref(), address))
+        .transpose()?;
+    match (&addr, &limit) {
+        (Some(addr), Some(limit)) => RATE_LIMITS.save(deps.storage, addr, limit)?,
+        (Some(addr), None) => RATE_LIMITS.remove(deps.storage, addr),
+        (None, Some(limit)) => GLOBAL_RATE_LIMIT.save(deps.storage, limit)?,
+        (None, None) => GLOBAL_RATE_LIMIT.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_rate_limit")
+        .add_attribute("address", address.unwrap_or_else(|| "global".to_string()))
+        .add_attribute(
+            "limit",
+            limit.map_or("None".to_string(), |limit| {
+                format!("{}/{}s", limit.amount, limit.window)
+            }),
+        ))
+}
+
+pub fn execute_schedule_operation(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    if matches!(
+        msg,
+        ExecuteMsg::ScheduleOperation { .. }
+            | ExecuteMsg::ExecuteOperation { .. }
+            | ExecuteMsg::CancelOperation { .. }
+    ) {
+        return Err(ContractError::InvalidScheduledOperation {});
+    }
+
+    let id = NEXT_OPERATION_ID.may_load(deps.storage)?.unwrap_or_default();
+    NEXT_OPERATION_ID.save(deps.storage, &(id + 1))?;
+    let token_info = TOKEN_INFO.load(deps.storage)?;
+    let operation = ScheduledOperation {
+        msg: to_json_binary(&msg)?,
+        proposer: info.sender,
+        ready_at: token_info.timelock_delay.after(&env.block),
+    };
+    SCHEDULED_OPERATIONS.save(deps.storage, id, &operation)?;
+
+    // The full payload is published so it can be reviewed before it runs
+    let event = Event::new("operation_scheduled")
+        .add_attribute("id", id.to_string())
+        .add_attribute("proposer", operation.proposer.as_str())
+        .add_attribute("msg", to_json_string(&msg)?)
+        .add_attribute("ready_at", operation.ready_at.to_string());
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("action", "schedule_operation")
+        .add_attribute("id", id.to_string()))
+}
+
+/// Runs a ready operation as its proposer, who must still be the owner
+pub fn execute_scheduled_operation(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    let operation = SCHEDULED_OPERATIONS
+        .may_load(deps.storage, id)?
+        .ok_or(ContractError::OperationNotFound { id })?;
+    if !operation.ready_at.is_expired(&env.block) {
+        return Err(ContractError::OperationNotReady {
+            id,
+            ready_at: operation.ready_at,
+        });
+    }
+    cw_ownable::assert_owner(deps.storage, &operation.proposer)?;
+    SCHEDULED_OPERATIONS.remove(deps.storage, id);
+
+    let info = MessageInfo {
+        sender: operation.proposer,
+        funds: vec![],
+    };
+    let res = dispatch(deps, env, info, from_json(&operation.msg)?)?;
+
+    Ok(res.add_event(Event::new("operation_executed").add_attribute("id", id.to_string())))
+}
+
+pub fn execute_cancel_operation(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    if !SCHEDULED_OPERATIONS.has(deps.storage, id) {
+        return Err(ContractError::OperationNotFound { id });
+    }
+    SCHEDULED_OPERATIONS.remove(deps.storage, id);
+
+    Ok(Response::new()
+        .add_event(Event::new("operation_cancelled").add_attribute("id", id.to_string()))
+        .add_attribute("action", "cancel_operation")
+        .add_attribute("id", id.to_string()))
+}
+
+pub fn execute_burn(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Burn)?;
+    cw_utils::nonpayable(&info)?;
+
+    let burner_addr = info.sender;
+    let fee = burn_tokens(deps, &burner_addr, amount)?;
 
     Ok(Response::new()
         .add_attribute("action", "burn")
         .add_attribute("burner", &burner_addr)
-        .add_attribute("amount", amount.to_string()))
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("fee", fee.to_string()))
+}
+
+pub fn execute_burn_from(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Burn)?;
+    cw_utils::nonpayable(&info)?;
+
+    if amount.is_zero() {
+        return Err(ContractError::InvalidAmount {});
+    }
+    let owner_addr = validate_addr(deps.as_ref(), &owner)?;
+
+    let spender = info.sender;
+    assert_not_frozen(deps.storage, &spender)?;
+    spend_allowance(deps.branch(), &env.block, &owner_addr, &spender, amount)?;
+
+    let fee = burn_tokens(deps, &owner_addr, amount)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "burn_from")
+        .add_attribute("owner", owner)
+        .add_attribute("spender", spender)
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("fee", fee.to_string()))
 }
@@ -1,9 +1,37 @@
 use crate::{
     error::ContractError,
-    state::{ALLOWANCES},
+    state::{
+        AllowanceInfo, Operation, Outflow, RateLimit, ACCRUED_FEES, ALLOWANCES,
+        ALLOWANCES_SPENDER, BALANCES, FEE_EXEMPT, FROZEN, GLOBAL_RATE_LIMIT, OUTFLOWS, PAUSED,
+        RATE_LIMITS, TOKEN_INFO,
+    },
 };
-use cosmwasm_std::{Addr, Decimal, Deps, DepsMut};
-const DECIMAL_ONE_ATOMICS: u128 = Decimal::one().atomics().u128();
+use crate::msg::{
+    EmbeddedLogo, ExecuteMsg, FeeRecipient, FeeRounding, FeeShare, FeeTarget, FeeTier, Logo,
+};
+use cosmwasm_std::{
+    Addr, Binary, BlockInfo, CheckedMultiplyFractionError, Decimal, Deps, DepsMut, OverflowError,
+    OverflowOperation, StdResult, Storage, Timestamp, Uint128, Uint256,
+};
+use cw_utils::{Duration, Expiration};
+
+const LOGO_SIZE_CAP: usize = 5 * 1024;
+
+const MAX_FEE_RECIPIENTS: usize = 10;
+
+const MAX_FEE_TIERS: usize = 10;
+
+// Outflows are summed per 1/24th of the rate limit window, an account keeps at most this many
+const OUTFLOW_BUCKETS: usize = 24;
+
+// Longest rate limit window and delays in seconds, one year
+pub const MAX_DURATION_SECONDS: u64 = 365 * 24 * 60 * 60;
+
+// Longest delays in blocks, about a year at six second blocks
+const MAX_DURATION_BLOCKS: u64 = MAX_DURATION_SECONDS / 6;
+
+// First bytes of every PNG file
+const PNG_HEADER: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
 
 pub fn validate_addr(deps: Deps, address: &str) -> Result<Addr, ContractError> {
     match deps.api.addr_validate(address) {
@@ -14,68 +42,669 @@ pub fn validate_addr(deps: Deps, address: &str) -> Result<Addr, ContractError> {
     }
 }
 
+/// Checks a fee rate against the configured maximum
+pub fn validate_fee_rate(fee_rate: Decimal, max_fee_rate: Decimal) -> Result<(), ContractError> {
+    if fee_rate > max_fee_rate {
+        return Err(ContractError::InvalidFees {
+            allowed: max_fee_rate,
+            passed: fee_rate,
+        });
+    }
+    Ok(())
+}
+
+/// Checks that `delay` is at most about a year, so it cannot overflow once added to a block
+pub fn validate_delay(delay: Duration) -> Result<Duration, ContractError> {
+    let too_long = match delay {
+        Duration::Height(blocks) => blocks > MAX_DURATION_BLOCKS,
+        Duration::Time(seconds) => seconds > MAX_DURATION_SECONDS,
+    };
+    if too_long {
+        return Err(ContractError::InvalidDelay { delay });
+    }
+    Ok(delay)
+}
+
+/// Checks that the absolute fee bounds do not cross
+pub fn validate_fee_bounds(
+    min_fee: Option<Uint128>,
+    max_fee: Option<Uint128>,
+) -> Result<(), ContractError> {
+    if let (Some(min_fee), Some(max_fee)) = (min_fee, max_fee) {
+        if min_fee > max_fee {
+            return Err(ContractError::InvalidFeeBounds { min_fee, max_fee });
+        }
+    }
+    Ok(())
+}
+
+/// Writes an allowance to both the owner and the spender index
+fn save_allowance(
+    deps: DepsMut,
+    owner: &Addr,
+    spender: &Addr,
+    allowance: &AllowanceInfo,
+) -> Result<(), ContractError> {
+    ALLOWANCES.save(deps.storage, (owner, spender), allowance)?;
+    ALLOWANCES_SPENDER.save(deps.storage, (spender, owner), allowance)?;
+    Ok(())
+}
+
 pub fn increase_allowance(
     deps: DepsMut,
+    block: &BlockInfo,
     owner: &Addr,
     spender: &Addr,
-    amount: u128,
+    amount: Uint128,
+    expires: Option<Expiration>,
 ) -> Result<(), ContractError> {
     // Load Current Allowance
-    let current_allowance = ALLOWANCES
+    let mut allowance = ALLOWANCES
         .may_load(deps.storage, (owner, spender))?
-        .unwrap_or(0); // Treat `None` as 0 if no allowance exists
+        .unwrap_or_default(); // Treat `None` as an empty allowance that never expires
 
-    let new_allowance = current_allowance
+    if let Some(expires) = expires {
+        if expires.is_expired(block) {
+            return Err(ContractError::InvalidExpiration {});
+        }
+        allowance.expires = expires;
+    }
+
+    allowance.amount = allowance
+        .amount
         .checked_add(amount)
-        .ok_or(ContractError::Overflow {})?;
+        .map_err(|_| ContractError::Overflow {})?;
 
     // Save the updated allowance
 
-    ALLOWANCES.save(deps.storage, (owner, spender), &new_allowance)?;
+    save_allowance(deps, owner, spender, &allowance)?;
 
     Ok(()) // No need to Return Anything
 }
 
 pub fn decrease_allowance(
     deps: DepsMut,
+    block: &BlockInfo,
     owner: &Addr,
     spender: &Addr,
-    amount: u128,
+    amount: Uint128,
+    expires: Option<Expiration>,
 ) -> Result<(), ContractError> {
     // Load current allowance
-    let current_allowance = ALLOWANCES
+    let mut allowance = ALLOWANCES
         .may_load(deps.storage, (owner, spender))?
-        .unwrap_or(0);
+        .unwrap_or_default();
 
     // Ensure allowance doesn't go below zero
-    if current_allowance < amount {
+    if allowance.amount < amount {
         return Err(ContractError::InsufficientAllowance {
-            allowance: current_allowance,
+            allowance: allowance.amount,
             required: amount,
         });
     }
 
+    if let Some(expires) = expires {
+        if expires.is_expired(block) {
+            return Err(ContractError::InvalidExpiration {});
+        }
+        allowance.expires = expires;
+    }
+
     // Safely subtract from the current allowance
-    let new_allowance = current_allowance.saturating_sub(amount);
+    allowance.amount = allowance.amount.checked_sub(amount)?;
 
     // Save the new allowance
-    ALLOWANCES.save(deps.storage, (owner, spender), &new_allowance)?;
+    save_allowance(deps, owner, spender, &allowance)?;
+
+    Ok(())
+}
+
+/// Consumes `amount` of the allowance `owner` granted to `spender`
+pub fn spend_allowance(
+    deps: DepsMut,
+    block: &BlockInfo,
+    owner: &Addr,
+    spender: &Addr,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let mut allowance = ALLOWANCES
+        .may_load(deps.storage, (owner, spender))?
+        .unwrap_or_default();
+
+    if allowance.expires.is_expired(block) {
+        return Err(ContractError::Expired {});
+    }
+
+    if allowance.amount < amount {
+        return Err(ContractError::InsufficientAllowance {
+            allowance: allowance.amount,
+            required: amount,
+        });
+    }
+
+    allowance.amount = allowance.amount.checked_sub(amount)?;
+    save_allowance(deps, owner, spender, &allowance)?;
 
     Ok(())
 }
 
-pub fn calculate_fee(amount: u128, fee: Decimal) -> Result<(u128, u128), ContractError> {
+pub fn calculate_fee(
+    amount: Uint128,
+    fee: Decimal,
+    tiers: &[FeeTier<Decimal>],
+    rounding: FeeRounding,
+    min_fee: Option<Uint128>,
+    max_fee: Option<Uint128>,
+) -> Result<(Uint128, Uint128), ContractError> {
+    // The highest tier reached by the amount replaces the base rate
+    let fee = tiers
+        .iter()
+        .rev()
+        .find(|tier| amount >= tier.threshold)
+        .map_or(fee, |tier| tier.rate);
 
-    // Calculate fees
-    let fee_decimal = fee * Decimal::from_ratio(amount, 1u128);
+    // Calculate fees with the configured rounding
+    let mut fee_final = match rounding {
+        FeeRounding::Floor => amount.checked_mul_floor(fee),
+        FeeRounding::Ceil => amount.checked_mul_ceil(fee),
+        FeeRounding::HalfEven => mul_half_even(amount, fee),
+    }
+    .map_err(|_| OverflowError::new(OverflowOperation::Mul))?;
 
-       // Converting fee to u128 directly and descaling at the same time 
-    let fee_final = fee_decimal.atomics().u128() / DECIMAL_ONE_ATOMICS;
+    // Clamp to the absolute bounds, the amount has to cover at least the minimum fee
+    if let Some(min_fee) = min_fee {
+        if amount < min_fee {
+            return Err(ContractError::BelowMinFee { amount, min_fee });
+        }
+        fee_final = fee_final.max(min_fee);
+    }
+    if let Some(max_fee) = max_fee {
+        fee_final = fee_final.min(max_fee);
+    }
 
     // Subtract fee from amount
-    let deducted_amt = amount.saturating_sub(fee_final);
+    let deducted_amt = amount.checked_sub(fee_final)?;
 
     // Return results
     Ok((deducted_amt, fee_final))
 }
 
+/// `amount * fee` rounded to the nearest unit, ties go to the even neighbour
+fn mul_half_even(amount: Uint128, fee: Decimal) -> Result<Uint128, CheckedMultiplyFractionError> {
+    let one = Uint256::from(Decimal::one().atomics());
+    let product = amount.full_mul(fee.atomics());
+    let quotient = product / one;
+    let remainder = product % one;
+
+    let twice_remainder = remainder + remainder;
+    let round_up = twice_remainder > one
+        || (twice_remainder == one && quotient % Uint256::from(2u8) == Uint256::one());
+    let rounded = if round_up {
+        quotient + Uint256::one()
+    } else {
+        quotient
+    };
+    Ok(rounded.try_into()?)
+}
+
+/// Checks that the tiers are sorted by strictly increasing threshold and within the maximum rate
+pub fn validate_fee_tiers(
+    tiers: Vec<FeeTier>,
+    max_fee_rate: Decimal,
+) -> Result<Vec<FeeTier<Decimal>>, ContractError> {
+    if tiers.len() > MAX_FEE_TIERS {
+        return Err(ContractError::InvalidFeeTiers {
+            reason: format!("at most {} tiers are allowed", MAX_FEE_TIERS),
+        });
+    }
+
+    let mut validated: Vec<FeeTier<Decimal>> = Vec::with_capacity(tiers.len());
+    for tier in tiers {
+        if let Some(previous) = validated.last() {
+            if tier.threshold <= previous.threshold {
+                return Err(ContractError::InvalidFeeTiers {
+                    reason: "thresholds must be strictly increasing".to_string(),
+                });
+            }
+        }
+        let rate = tier.rate.to_decimal();
+        validate_fee_rate(rate, max_fee_rate)?;
+        validated.push(FeeTier {
+            threshold: tier.threshold,
+            rate,
+        });
+    }
+    Ok(validated)
+}
+
+/// Validates the recipient addresses and weights of a fee split
+pub fn validate_fee_recipients(
+    deps: Deps,
+    recipients: Vec<FeeRecipient>,
+) -> Result<Vec<FeeRecipient<Addr>>, ContractError> {
+    if recipients.is_empty() || recipients.len() > MAX_FEE_RECIPIENTS {
+        return Err(ContractError::InvalidFeeRecipients {
+            reason: format!("expected between 1 and {MAX_FEE_RECIPIENTS} recipients"),
+        });
+    }
+
+    recipients
+        .into_iter()
+        .map(|recipient| {
+            if recipient.weight == 0 {
+                return Err(ContractError::InvalidFeeRecipients {
+                    reason: "weights must be greater than zero".to_string(),
+                });
+            }
+            let target = match recipient.target {
+                FeeTarget::Address(addr) => FeeTarget::Address(validate_addr(deps, &addr)?),
+                FeeTarget::Burn => FeeTarget::Burn,
+            };
+            Ok(FeeRecipient {
+                target,
+                weight: recipient.weight,
+            })
+        })
+        .collect()
+}
+
+/// Formats a fee split for event attributes, e.g. `treasury:3,burn:1`
+pub fn join_fee_recipients<A: std::fmt::Display>(recipients: &[FeeRecipient<A>]) -> String {
+    recipients
+        .iter()
+        .map(|recipient| recipient.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Splits `fee` by weight. Rounding dust goes one unit at a time to the recipients
+/// in list order, so the shares always add up to `fee`
+pub fn split_fee(recipients: &[FeeRecipient<Addr>], fee: Uint128) -> Vec<Uint128> {
+    let total_weight: u128 = recipients.iter().map(|r| u128::from(r.weight)).sum();
+    let mut shares: Vec<Uint128> = recipients
+        .iter()
+        .map(|r| fee.multiply_ratio(r.weight, total_weight))
+        .collect();
+
+    let distributed: Uint128 = shares.iter().sum();
+    let dust = (fee - distributed).u128() as usize; // Always less than the number of recipients
+    for share in shares.iter_mut().take(dust) {
+        *share += Uint128::one();
+    }
+    shares
+}
+
+/// Pairs each recipient with its share of `fee`, empty when there is no fee
+pub fn fee_shares(recipients: &[FeeRecipient<Addr>], fee: Uint128) -> Vec<FeeShare<Addr>> {
+    if fee.is_zero() {
+        return vec![];
+    }
+    recipients
+        .iter()
+        .zip(split_fee(recipients, fee))
+        .map(|(recipient, amount)| FeeShare {
+            target: recipient.target.clone(),
+            amount,
+        })
+        .collect()
+}
+
+/// Fee charged on a mint or burn at `fee_rate`, no fee when the rate is unset.
+/// Returns `(net_amount, fee)`.
+pub fn calculate_supply_fee(
+    amount: Uint128,
+    fee_rate: Option<Decimal>,
+    rounding: FeeRounding,
+) -> Result<(Uint128, Uint128), ContractError> {
+    match fee_rate {
+        Some(fee_rate) => calculate_fee(amount, fee_rate, &[], rounding, None, None),
+        None => Ok((amount, Uint128::zero())),
+    }
+}
+
+/// Accrues each share of a fee to be claimed later,
+/// burned shares leave the circulating supply
+pub fn distribute_fee(deps: DepsMut, shares: &[FeeShare<Addr>]) -> Result<(), ContractError> {
+    let mut burned = Uint128::zero();
+    for share in shares {
+        match &share.target {
+            FeeTarget::Address(addr) => {
+                let accrued = ACCRUED_FEES.may_load(deps.storage, addr)?.unwrap_or_default();
+                ACCRUED_FEES.save(deps.storage, addr, &accrued.checked_add(share.amount)?)?;
+            }
+            FeeTarget::Burn => burned = burned.checked_add(share.amount)?,
+        }
+    }
+
+    if !burned.is_zero() {
+        let mut token_info = TOKEN_INFO.load(deps.storage)?;
+        token_info.circulating_supply = token_info.circulating_supply.checked_sub(burned)?;
+        TOKEN_INFO.save(deps.storage, &token_info)?;
+    }
+
+    Ok(())
+}
+
+/// Fails with `AccountFrozen` if `addr` is on the freeze list
+pub fn assert_not_frozen(storage: &dyn Storage, addr: &Addr) -> Result<(), ContractError> {
+    if FROZEN.has(storage, addr) {
+        return Err(ContractError::AccountFrozen {
+            address: addr.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Outflow limit of `addr`, its own limit takes precedence over the global one
+pub fn rate_limit_of(storage: &dyn Storage, addr: &Addr) -> StdResult<Option<RateLimit>> {
+    match RATE_LIMITS.may_load(storage, addr)? {
+        Some(limit) => Ok(Some(limit)),
+        None => GLOBAL_RATE_LIMIT.may_load(storage),
+    }
+}
+
+// Length in seconds of the buckets outflows are summed in under `limit`
+fn bucket_len(limit: &RateLimit) -> u64 {
+    (limit.window / OUTFLOW_BUCKETS as u64).max(1)
+}
+
+// Outflows of `addr` that still count against `limit` at `block`. A bucket is only dropped
+// once its last second is a full window old, so nothing in it stops counting early
+fn recent_outflows(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    addr: &Addr,
+    limit: &RateLimit,
+) -> StdResult<Vec<Outflow>> {
+    let mut outflows = OUTFLOWS.may_load(storage, addr)?.unwrap_or_default();
+    let span = bucket_len(limit) + limit.window;
+    outflows.retain(|outflow| outflow.at.plus_seconds(span) > block.time);
+    Ok(outflows)
+}
+
+fn remaining_in_window(limit: &RateLimit, outflows: &[Outflow]) -> Uint128 {
+    let spent = outflows.iter().map(|outflow| outflow.amount).sum::<Uint128>();
+    limit.amount.saturating_sub(spent)
+}
+
+/// Amount `addr` can still send in the current window, `None` when it is not limited
+pub fn remaining_outflow(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    addr: &Addr,
+) -> StdResult<Option<Uint128>> {
+    rate_limit_of(storage, addr)?
+        .map(|limit| {
+            let outflows = recent_outflows(storage, block, addr, &limit)?;
+            Ok(remaining_in_window(&limit, &outflows))
+        })
+        .transpose()
+}
+
+/// Counts `amount` against the outflow limit of `addr`, fails with `RateLimitExceeded` past it
+pub fn record_outflow(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    addr: &Addr,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let Some(limit) = rate_limit_of(storage, addr)? else {
+        return Ok(());
+    };
+    if amount.is_zero() {
+        return Ok(());
+    }
+    let mut outflows = recent_outflows(storage, block, addr, &limit)?;
+    let remaining = remaining_in_window(&limit, &outflows);
+    if amount > remaining {
+        return Err(ContractError::RateLimitExceeded { remaining });
+    }
+
+    // Each outflow is added to the bucket it falls in and counts as long as that bucket does
+    let bucket_len = bucket_len(&limit);
+    let at = Timestamp::from_seconds(block.time.seconds() / bucket_len * bucket_len);
+    match outflows.last_mut() {
+        Some(last) if last.at == at => last.amount = last.amount.checked_add(amount)?,
+        _ => outflows.push(Outflow { at, amount }),
+    }
+    // Buckets left over from a longer window are folded into the next one
+    while outflows.len() > OUTFLOW_BUCKETS {
+        let oldest = outflows.remove(0);
+        outflows[0].amount = outflows[0].amount.checked_add(oldest.amount)?;
+    }
+    OUTFLOWS.save(storage, addr, &outflows)?;
+    Ok(())
+}
+
+/// Checks `Transfer` and `SimulateTransfer` share, everything but the sender's balance
+pub fn assert_transfer_allowed(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    from: &Addr,
+    to: &Addr,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    assert_not_paused(storage, Operation::Transfer)?;
+    if amount.is_zero() {
+        return Err(ContractError::InvalidAmount {});
+    }
+    assert_not_frozen(storage, from)?;
+    assert_not_frozen(storage, to)?;
+    if let Some(remaining) = remaining_outflow(storage, block, from)? {
+        if amount > remaining {
+            return Err(ContractError::RateLimitExceeded { remaining });
+        }
+    }
+    Ok(())
+}
+
+/// Whether `msg` sent by `sender` is an owner action that has to go through the timelock.
+/// Defensive actions stay immediate: pausing, freezing and tightening a rate limit.
+/// Fee changes have their own delay
+pub fn requires_timelock(
+    storage: &dyn Storage,
+    sender: &Addr,
+    msg: &ExecuteMsg,
+) -> StdResult<bool> {
+    let privileged = match msg {
+        ExecuteMsg::Mint { .. }
+        | ExecuteMsg::UpdateOwnership(_)
+        | ExecuteMsg::GrantRole { .. }
+        | ExecuteMsg::RevokeRole { .. }
+        | ExecuteMsg::UpdateMinter { .. }
+        | ExecuteMsg::SetMinterCap { .. } => true,
+        ExecuteMsg::SetRateLimit { address, limit } => {
+            loosens_rate_limit(storage, address.as_deref(), limit.as_ref())?
+        }
+        _ => false,
+    };
+    Ok(privileged && cw_ownable::is_owner(storage, sender)?)
+}
+
+// Whether `limit` lets more tokens out than the limit in effect, removing a limit always does
+fn loosens_rate_limit(
+    storage: &dyn Storage,
+    address: Option<&str>,
+    limit: Option<&RateLimit>,
+) -> StdResult<bool> {
+    // Only used as a lookup key, the handler validates the address
+    let current = match address {
+        Some(address) => rate_limit_of(storage, &Addr::unchecked(address))?,
+        None => GLOBAL_RATE_LIMIT.may_load(storage)?,
+    };
+    Ok(match (current, limit) {
+        (_, None) => true,
+        (None, Some(_)) => false,
+        (Some(current), Some(limit)) => {
+            limit.amount > current.amount || limit.window < current.window
+        }
+    })
+}
+
+/// Fails with `Paused` while the pauser has halted `operation`
+pub fn assert_not_paused(storage: &dyn Storage, operation: Operation) -> Result<(), ContractError> {
+    let paused = PAUSED.may_load(storage)?.unwrap_or_default();
+    if paused.is_paused(operation) {
+        return Err(ContractError::Paused { operation });
+    }
+    Ok(())
+}
+
+/// Whether the exemption list waives the fee for a transfer from `from` to `to`
+pub fn is_fee_exempt(storage: &dyn Storage, from: &Addr, to: &Addr) -> StdResult<bool> {
+    let sender_exempt = FEE_EXEMPT
+        .may_load(storage, from)?
+        .is_some_and(|kind| kind.covers_sender());
+    let recipient_exempt = FEE_EXEMPT
+        .may_load(storage, to)?
+        .is_some_and(|kind| kind.covers_recipient());
+    Ok(sender_exempt || recipient_exempt)
+}
+
+/// Fee outcome of a transfer, shared by execution and the `SimulateTransfer` query
+pub struct TransferQuote {
+    pub net_amount: Uint128,
+    pub fee: Uint128,
+    pub fee_breakdown: Vec<FeeShare<Addr>>,
+    pub exempt: bool,
+}
+
+/// Computes the fee of a transfer from `from` to `to` and its split among the fee recipients
+pub fn quote_transfer(
+    storage: &dyn Storage,
+    from: &Addr,
+    to: &Addr,
+    amount: Uint128,
+) -> Result<TransferQuote, ContractError> {
+    let token_info = TOKEN_INFO.load(storage)?;
+    let exempt = is_fee_exempt(storage, from, to)?;
+    // Exempt transfers skip the minimum fee as well
+    let (net_amount, fee) = if exempt {
+        (amount, Uint128::zero())
+    } else {
+        calculate_fee(
+            amount,
+            token_info.fee_rate,
+            &token_info.fee_tiers,
+            token_info.fee_rounding,
+            token_info.min_fee,
+            token_info.max_fee,
+        )?
+    };
+
+    Ok(TransferQuote {
+        net_amount,
+        fee,
+        fee_breakdown: fee_shares(&token_info.fee_recipients, fee),
+        exempt,
+    })
+}
+
+/// Moves `amount` from `from` to `to`, splitting the fee among the fee recipients.
+/// Returns `(net_amount, fee)`.
+pub fn transfer_with_fee(
+    deps: DepsMut,
+    from: &Addr,
+    to: &Addr,
+    amount: Uint128,
+) -> Result<(Uint128, Uint128), ContractError> {
+    // Frozen funds cannot leave an account and nothing can be sent to it
+    assert_not_frozen(deps.storage, from)?;
+    assert_not_frozen(deps.storage, to)?;
+
+    let TransferQuote {
+        net_amount,
+        fee,
+        fee_breakdown,
+        ..
+    } = quote_transfer(deps.storage, from, to, amount)?;
+
+    // Ensure the sender has enough balance to cover the amount (fee included)
+    let from_balance = BALANCES.may_load(deps.storage, from)?.unwrap_or_default();
+    if from_balance < amount {
+        return Err(ContractError::InsufficientFunds {
+            amount,
+            balance: from_balance,
+        });
+    }
+    BALANCES.save(deps.storage, from, &from_balance.checked_sub(amount)?)?;
+
+    // Balances are re-read after each write so self transfers cannot mint tokens
+    let to_balance = BALANCES.may_load(deps.storage, to)?.unwrap_or_default();
+    let new_to_balance = to_balance.checked_add(net_amount)?;
+    BALANCES.save(deps.storage, to, &new_to_balance)?;
+
+    distribute_fee(deps, &fee_breakdown)?;
+
+    Ok((net_amount, fee))
+}
+
+/// Removes `amount` from `owner`, the burn fee goes to the fee recipients and
+/// the rest leaves the circulating supply. Returns the fee.
+pub fn burn_tokens(
+    deps: DepsMut,
+    owner: &Addr,
+    amount: Uint128,
+) -> Result<Uint128, ContractError> {
+    assert_not_frozen(deps.storage, owner)?;
+    if amount.is_zero() {
+        return Err(ContractError::InvalidAmount {});
+    }
+
+    let owner_balance = BALANCES.may_load(deps.storage, owner)?.unwrap_or_default();
+    if owner_balance < amount {
+        return Err(ContractError::InsufficientFunds {
+            amount,
+            balance: owner_balance,
+        });
+    }
+    BALANCES.save(deps.storage, owner, &owner_balance.checked_sub(amount)?)?;
+
+    let mut token_info = TOKEN_INFO.load(deps.storage)?;
+    let (burned, fee) =
+        calculate_supply_fee(amount, token_info.burn_fee_rate, token_info.fee_rounding)?;
+    token_info.circulating_supply = token_info.circulating_supply.checked_sub(burned)?;
+    TOKEN_INFO.save(deps.storage, &token_info)?;
+
+    distribute_fee(deps, &fee_shares(&token_info.fee_recipients, fee))?;
+
+    Ok(fee)
+}
+
+/// Checks that an embedded logo is a small SVG or PNG, URLs are not checked
+pub fn verify_logo(logo: &Logo) -> Result<(), ContractError> {
+    match logo {
+        Logo::Embedded(EmbeddedLogo::Svg(logo)) => verify_xml_logo(logo),
+        Logo::Embedded(EmbeddedLogo::Png(logo)) => verify_png_logo(logo),
+        Logo::Url(_) => Ok(()),
+    }
+}
+
+fn verify_xml_logo(logo: &Binary) -> Result<(), ContractError> {
+    if logo.len() > LOGO_SIZE_CAP {
+        return Err(ContractError::LogoTooBig {});
+    }
+
+    // Only a rough check that the document starts like an SVG, leading whitespace allowed
+    let preamble = logo
+        .iter()
+        .skip_while(|b| b.is_ascii_whitespace())
+        .take(5)
+        .copied()
+        .collect::<Vec<u8>>();
+    if preamble != b"<?xml" && preamble.get(..4) != Some(b"<svg".as_slice()) {
+        return Err(ContractError::InvalidXmlPreamble {});
+    }
+
+    Ok(())
+}
+
+fn verify_png_logo(logo: &Binary) -> Result<(), ContractError> {
+    if logo.len() > LOGO_SIZE_CAP {
+        return Err(ContractError::LogoTooBig {});
+    }
+    if !logo.starts_with(&PNG_HEADER) {
+        return Err(ContractError::InvalidPngHeader {});
+    }
+    Ok(())
+}
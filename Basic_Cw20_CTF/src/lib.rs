@@ -1,8 +1,15 @@
+// The package name is kept for existing deploy scripts and wasm artifacts, this only
+// silences the lint on that crate name
+#![allow(non_snake_case)]
+
 pub mod contract;
 pub mod error;
 pub mod execute;
 pub mod helpers;
+pub mod migrations;
 pub mod msg;
+pub mod query;
+pub mod roles;
 pub mod state;
 
 pub use crate::error::ContractError;
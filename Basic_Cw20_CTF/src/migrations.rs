@@ -0,0 +1,108 @@
+// @dev Storage transforms for instances deployed with an older storage layout.
+// Old layouts are redeclared here under the same storage keys so they can be read back.
+
+use cosmwasm_std::{Addr, Decimal, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::msg::{FeeRecipient, FeeRounding, FeeTarget};
+use crate::roles::{grant_role, Role};
+use crate::state::{
+    AllowanceInfo, TokenInfo, ALLOWANCES, ALLOWANCES_SPENDER, BALANCES, DEFAULT_FEE_CHANGE_DELAY,
+    DEFAULT_MAX_FEE_RATE, DEFAULT_TIMELOCK_DELAY, MINTER, TOKEN_INFO,
+};
+
+/// Runs every transform needed to bring storage written by `from` up to the current layout
+pub fn migrate_state(storage: &mut dyn Storage, from: &Version) -> StdResult<()> {
+    if *from < Version::new(0, 2, 0) {
+        migrate_amounts_to_uint128(storage)?;
+        initialize_roles(storage)?;
+    }
+    Ok(())
+}
+
+/// Layout of v0.1.0, where every amount was a raw `u128` stored as a JSON number
+pub mod v0_1 {
+    use super::*;
+
+    pub const TOKEN_INFO: Item<TokenInfo> = Item::new("token_info");
+    pub const BALANCES: Map<&Addr, u128> = Map::new("balances");
+    pub const ALLOWANCES: Map<(&Addr, &Addr), u128> = Map::new("allowances");
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+    pub struct TokenInfo {
+        pub name: String,
+        pub symbol: String,
+        pub decimals: u8,
+        pub circulating_supply: u128,
+        pub max_supply: u128,
+        pub owner: Addr,
+        pub fee_collector: Addr,
+        pub fee_rate: Decimal,
+    }
+}
+
+/// Rewrites v0.1.0 token info, balances and allowances as `Uint128`.
+/// Allowances are also written to the spender index and never expire.
+pub fn migrate_amounts_to_uint128(storage: &mut dyn Storage) -> StdResult<()> {
+    let legacy = v0_1::TOKEN_INFO.load(storage)?;
+    TOKEN_INFO.save(
+        storage,
+        &TokenInfo {
+            name: legacy.name,
+            symbol: legacy.symbol,
+            decimals: legacy.decimals,
+            circulating_supply: Uint128::new(legacy.circulating_supply),
+            max_supply: Uint128::new(legacy.max_supply),
+            fee_recipients: vec![FeeRecipient {
+                target: FeeTarget::Address(legacy.fee_collector),
+                weight: 1,
+            }],
+            fee_rate: legacy.fee_rate,
+            fee_tiers: vec![],
+            max_fee_rate: DEFAULT_MAX_FEE_RATE,
+            fee_rounding: FeeRounding::Floor,
+            mint_fee_rate: None,
+            burn_fee_rate: None,
+            min_fee: None,
+            max_fee: None,
+            fee_change_delay: DEFAULT_FEE_CHANGE_DELAY,
+            timelock_delay: DEFAULT_TIMELOCK_DELAY,
+        },
+    )?;
+
+    // Entries are collected first since storage cannot be written while iterating
+    let balances = v0_1::BALANCES
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (addr, balance) in balances {
+        BALANCES.save(storage, &addr, &Uint128::new(balance))?;
+    }
+
+    let allowances = v0_1::ALLOWANCES
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for ((owner, spender), amount) in allowances {
+        let allowance = AllowanceInfo {
+            amount: Uint128::new(amount),
+            ..AllowanceInfo::default()
+        };
+        ALLOWANCES.save(storage, (&owner, &spender), &allowance)?;
+        ALLOWANCES_SPENDER.save(storage, (&spender, &owner), &allowance)?;
+    }
+
+    Ok(())
+}
+
+/// Hands every role to the current owner, who held all of these powers before v0.2.0
+pub fn initialize_roles(storage: &mut dyn Storage) -> StdResult<()> {
+    if let Some(owner) = cw_ownable::get_ownership(storage)?.owner {
+        for role in Role::ALL {
+            grant_role(storage, role, &owner)?;
+        }
+        MINTER.save(storage, &owner)?;
+    }
+    Ok(())
+}
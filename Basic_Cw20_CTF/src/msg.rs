@@ -1,63 +1,508 @@
 //Inbterfaces for Initialization, Execution, and Querying
 use cosmwasm_schema::QueryResponses;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, CosmosMsg, Decimal, StdResult, Timestamp, Uint128, WasmMsg,
+};
 use schemars::JsonSchema;
+use std::fmt;
 use serde::{Deserialize, Serialize};
 use cw_ownable::cw_ownable_execute;
+use cw_utils::{Duration, Expiration};
+
+use crate::roles::Role;
+use crate::state::{ExemptionKind, Operation, PausedOperations, RateLimit};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub name: String,
     pub symbol: String,
     pub decimals: u8,
-    pub initial_supply: u128,
-    pub max_supply: u128,
+    pub initial_supply: Uint128,
+    pub max_supply: Uint128,
     pub owner: String,
-    pub fee_collector: String,
-    pub fee_rate: u128,
+    /// Weighted split of every fee, at least one recipient
+    pub fee_recipients: Vec<FeeRecipient>,
+    pub fee_rate: FeeRate,
+    /// Rates for larger transfers, sorted by threshold, `fee_rate` applies below the first one
+    #[serde(default)]
+    pub fee_tiers: Vec<FeeTier>,
+    /// Upper bound for the fee rate, defaults to 3%
+    pub max_fee_rate: Option<Decimal>,
+    /// Defaults to rounding the fee down
+    pub fee_rounding: Option<FeeRounding>,
+    /// Fees on mints and burns paid to the fee recipients, none by default
+    pub mint_fee_rate: Option<FeeRate>,
+    pub burn_fee_rate: Option<FeeRate>,
+    /// Absolute bounds for the fee of a single transfer, unbounded by default
+    pub min_fee: Option<Uint128>,
+    pub max_fee: Option<Uint128>,
+    /// Notice period for fee changes in blocks or seconds, defaults to one day, at most a year
+    pub fee_change_delay: Option<Duration>,
+    /// Wait before a scheduled operation can be executed, defaults to two days, at most a year
+    pub timelock_delay: Option<Duration>,
+    /// Granted the minter role, defaults to the owner with no cap
+    pub mint: Option<MinterResponse>,
+    /// Granted the pauser role, defaults to the owner
+    pub pauser: Option<String>,
+    pub marketing: Option<InstantiateMarketingInfo>,
+}
+
+/// Fee rate in basis points or as a decimal fraction,
+/// e.g. `{"bps": 10}` or `{"decimal": "0.001"}` for 0.1%
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeRate {
+    Bps(u64),
+    Decimal(Decimal),
+}
+
+impl FeeRate {
+    pub fn to_decimal(&self) -> Decimal {
+        match self {
+            FeeRate::Bps(bps) => Decimal::bps(*bps),
+            FeeRate::Decimal(rate) => *rate,
+        }
+    }
+}
+
+/// Rounding applied to the percentage part of the fee
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeRounding {
+    /// Rounds down, in favour of the sender
+    #[default]
+    Floor,
+    /// Rounds up, in favour of the fee recipients
+    Ceil,
+    /// Rounds to the nearest unit, ties to even
+    HalfEven,
+}
+
+/// Destination of a share of each fee, `A` is `Addr` once validated
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeTarget<A = String> {
+    Address(A),
+    /// Destroys the share, reducing the circulating supply
+    Burn,
+}
+
+/// Receives `weight / total weight` of each fee
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct FeeRecipient<A = String> {
+    pub target: FeeTarget<A>,
+    pub weight: u64,
+}
+
+/// Amount of a single fee paid to one target
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct FeeShare<A = String> {
+    pub target: FeeTarget<A>,
+    pub amount: Uint128,
+}
+
+/// Rate charged on transfers of at least `threshold`, `R` is `Decimal` once validated
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeTier<R = FeeRate> {
+    pub threshold: Uint128,
+    pub rate: R,
+}
+
+impl<A: fmt::Display> fmt::Display for FeeRecipient<A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.target {
+            FeeTarget::Address(addr) => write!(f, "{}:{}", addr, self.weight),
+            FeeTarget::Burn => write!(f, "burn:{}", self.weight),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMarketingInfo {
+    pub project: Option<String>,
+    pub description: Option<String>,
+    pub marketing: Option<String>,
+    pub logo: Option<Logo>,
+}
+
+/// A logo is either linked by URL or embedded on chain
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Logo {
+    Url(String),
+    Embedded(EmbeddedLogo),
 }
 
+/// Embedded logos are limited to 5KB
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddedLogo {
+    Svg(Binary),
+    Png(Binary),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[cw_ownable_execute]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub enum ExecuteMsg {
     Transfer {
         recipient: String,
-        amount: u128,
+        amount: Uint128,
+    },
+    /// Transfers to a contract and calls `Receive` on it with the net amount
+    Send {
+        contract: String,
+        amount: Uint128,
+        msg: Binary,
     },
     TransferFrom {
         owner: String,
         recipient: String,
-        amount: u128,
+        amount: Uint128,
+    },
+    /// Spends an allowance to send the owner's tokens to a contract
+    SendFrom {
+        owner: String,
+        contract: String,
+        amount: Uint128,
+        msg: Binary,
     },
     Mint {
         recipient: String,
-        amount: u128,
+        amount: Uint128,
     },
     Burn {
-        amount: u128,
+        amount: Uint128,
+    },
+    /// Spends an allowance to burn the owner's tokens
+    BurnFrom {
+        owner: String,
+        amount: Uint128,
+    },
+    /// Marketing admin only. `None` keeps a field, an empty string clears it
+    UpdateMarketing {
+        project: Option<String>,
+        description: Option<String>,
+        marketing: Option<String>,
+    },
+    /// Marketing admin only, replaces the logo
+    UploadLogo(Logo),
+    /// Fee manager only, schedules a new fee rate, fee recipients and fee tiers to apply after
+    /// the fee change delay.
+    /// Replaces any change that is still pending
+    ProposeFeeChange {
+        fee_rate: FeeRate,
+        fee_recipients: Vec<FeeRecipient>,
+        #[serde(default)]
+        fee_tiers: Vec<FeeTier>,
+    },
+    /// Fee manager only, applies the pending fee change once its delay has passed
+    ApplyFeeChange {},
+    /// Moves the fees accrued to the sender into `recipient`'s balance, defaults to the sender
+    ClaimFees {
+        recipient: Option<String>,
+    },
+    /// Fee manager only, waives the transfer fee when `address` sends, receives or both
+    AddFeeExemption {
+        address: String,
+        kind: ExemptionKind,
+    },
+    /// Fee manager only
+    RemoveFeeExemption {
+        address: String,
+    },
+    /// Compliance officer only, blocks all funds moving from or to `address`
+    Freeze {
+        address: String,
+        reason: String,
+    },
+    /// Compliance officer only
+    Unfreeze {
+        address: String,
+    },
+    /// Owner only
+    GrantRole {
+        role: Role,
+        address: String,
+    },
+    /// Owner only
+    RevokeRole {
+        role: Role,
+        address: String,
+    },
+    /// Drops a role held by the sender
+    RenounceRole {
+        role: Role,
+    },
+    /// Pauser only, halts the given operations, every operation when `None`
+    Pause {
+        operations: Option<Vec<Operation>>,
+    },
+    /// Pauser only, resumes the given operations, every operation when `None`
+    Unpause {
+        operations: Option<Vec<Operation>>,
+    },
+    /// Owner only, limits the outflow of `address` or of every account without a limit of its
+    /// own when `None`. A `None` limit lifts it
+    SetRateLimit {
+        address: Option<String>,
+        limit: Option<RateLimit>,
+    },
+    /// Owner only, queues `msg` to run as the owner once the timelock delay has passed.
+    /// The owner's mints, role, minter and ownership changes must go through here, as well as
+    /// rate limits that are loosened or lifted
+    ScheduleOperation {
+        msg: Box<ExecuteMsg>,
+    },
+    /// Runs a scheduled operation once it is ready, callable by anyone
+    ExecuteOperation {
+        id: u64,
+    },
+    /// Owner only, drops a scheduled operation
+    CancelOperation {
+        id: u64,
+    },
+    /// Owner only, replaces the minter set at instantiate or by the last update with
+    /// `new_minter`, or removes it when `None`. Other minters are left untouched
+    UpdateMinter {
+        new_minter: Option<String>,
+        cap: Option<Uint128>,
+    },
+    /// Owner only, sets the supply cap of a holder of the minter role, uncapped when `None`
+    SetMinterCap {
+        minter: String,
+        cap: Option<Uint128>,
     },
     IncreaseAllowance {
         owner: String,
         spender: String,
-        amount: u128,
+        amount: Uint128,
+        expires: Option<Expiration>,
     },
     DecreaseAllowance {
         owner: String,
         spender: String,
-        amount: u128,
+        amount: Uint128,
+        expires: Option<Expiration>,
     },
 }
 
+/// Payload delivered to a receiving contract by `Send`, identical to the cw20 standard
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw20ReceiveMsg {
+    pub sender: String,
+    pub amount: Uint128,
+    pub msg: Binary,
+}
+
+impl Cw20ReceiveMsg {
+    /// Serializes the message wrapped in `ReceiverExecuteMsg::Receive`
+    pub fn into_json_binary(self) -> StdResult<Binary> {
+        to_json_binary(&ReceiverExecuteMsg::Receive(self))
+    }
+
+    /// Builds the `WasmMsg::Execute` that calls the receiving contract
+    pub fn into_cosmos_msg<T: Into<String>>(self, contract_addr: T) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg: self.into_json_binary()?,
+            funds: vec![],
+        }
+        .into())
+    }
+}
+
+/// The execute variant a contract must expose to receive tokens
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiverExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct TokenInfoResponse {
     pub name: String,
     pub symbol: String,
     pub decimals: u8,
-    pub total_supply: u128,
+    pub total_supply: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct BalanceResponse {
-    pub balance: u128,
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PauseInfoResponse {
+    pub paused: PausedOperations,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HasRoleResponse {
+    pub has_role: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoleMembersResponse {
+    pub members: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeScheduleResponse {
+    /// Rate below the first tier threshold
+    pub fee_rate: Decimal,
+    pub fee_tiers: Vec<FeeTier<Decimal>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateTransferResponse {
+    /// Amount credited to the recipient
+    pub net_amount: Uint128,
+    pub fee: Uint128,
+    /// Split of `fee` among the fee recipients, empty when no fee is charged
+    pub fee_breakdown: Vec<FeeShare>,
+    /// Whether the exemption list waived the fee
+    pub exempt: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AccruedFeesResponse {
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeExemptionInfo {
+    pub address: String,
+    pub kind: ExemptionKind,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeExemptionsResponse {
+    pub exemptions: Vec<FeeExemptionInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FrozenAccountInfo {
+    pub address: String,
+    pub reason: String,
+    pub frozen_at: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FrozenAccountsResponse {
+    pub accounts: Vec<FrozenAccountInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RateLimitResponse {
+    /// Limit in effect for the account, `None` when it is not limited
+    pub limit: Option<RateLimit>,
+    /// Amount the account can still send in the current window
+    pub remaining: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScheduledOperationResponse {
+    pub id: u64,
+    /// The queued `ExecuteMsg` as JSON
+    pub msg: Binary,
+    pub proposer: String,
+    pub ready_at: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScheduledOperationsResponse {
+    pub operations: Vec<ScheduledOperationResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MinterResponse {
+    pub minter: String,
+    pub cap: Option<Uint128>,
+}
+
+/// What is stored for the logo, embedded data is fetched with `DownloadLogo`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LogoInfo {
+    Url(String),
+    Embedded,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct MarketingInfoResponse {
+    pub project: Option<String>,
+    pub description: Option<String>,
+    pub logo: Option<LogoInfo>,
+    pub marketing: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DownloadLogoResponse {
+    pub mime_type: String,
+    pub data: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    /// `None` once ownership has been renounced
+    pub owner: Option<String>,
+    pub fee_recipients: Vec<FeeRecipient>,
+    pub fee_rate: Decimal,
+    pub max_fee_rate: Decimal,
+    pub fee_rounding: FeeRounding,
+    pub mint_fee_rate: Option<Decimal>,
+    pub burn_fee_rate: Option<Decimal>,
+    pub min_fee: Option<Uint128>,
+    pub max_fee: Option<Uint128>,
+    pub fee_change_delay: Duration,
+    pub timelock_delay: Duration,
+    pub max_supply: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingFeeChangeResponse {
+    pub fee_rate: Decimal,
+    pub fee_recipients: Vec<FeeRecipient>,
+    pub fee_tiers: Vec<FeeTier<Decimal>>,
+    pub ready_at: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllowanceResponse {
+    pub allowance: Uint128,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllowanceInfo {
+    pub spender: String,
+    pub allowance: Uint128,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllAllowancesResponse {
+    pub allowances: Vec<AllowanceInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpenderAllowanceInfo {
+    pub owner: String,
+    pub allowance: Uint128,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllSpenderAllowancesResponse {
+    pub allowances: Vec<SpenderAllowanceInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllAccountsResponse {
+    pub accounts: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
@@ -69,5 +514,117 @@ pub enum QueryMsg {
     /// Returns the balance of a specific address
     #[returns(BalanceResponse)]
     Balance { address: String },
+
+    /// Returns the owner and fee configuration
+    #[returns(ConfigResponse)]
+    Config {},
+
+    /// Returns the proposed fee change, `None` if there is none
+    #[returns(Option<PendingFeeChangeResponse>)]
+    PendingFeeChange {},
+
+    /// Returns the base fee rate and the tiers for larger transfers
+    #[returns(FeeScheduleResponse)]
+    FeeSchedule {},
+
+    /// Returns the outcome of a transfer as `Transfer` would execute it, without the balance check.
+    /// Fails whenever `Transfer` would, e.g. while paused, frozen or over the rate limit
+    #[returns(SimulateTransferResponse)]
+    SimulateTransfer {
+        sender: String,
+        recipient: String,
+        amount: Uint128,
+    },
+
+    /// Returns the fees accrued to a fee recipient and not yet claimed
+    #[returns(AccruedFeesResponse)]
+    AccruedFees { address: String },
+
+    /// Returns fee exempt accounts, paginated by address
+    #[returns(FeeExemptionsResponse)]
+    FeeExemptions {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns frozen accounts with the reason and time of the freeze, paginated by address
+    #[returns(FrozenAccountsResponse)]
+    FrozenAccounts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the outflow limit of `address` and what is left of it in the current window
+    #[returns(RateLimitResponse)]
+    RateLimit { address: String },
+
+    /// Returns the scheduled operation with `id`, fails once it was executed or cancelled
+    #[returns(ScheduledOperationResponse)]
+    ScheduledOperation { id: u64 },
+
+    /// Returns the operations waiting in the timelock queue, paginated by id
+    #[returns(ScheduledOperationsResponse)]
+    ScheduledOperations {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Returns whether `address` holds `role`
+    #[returns(HasRoleResponse)]
+    HasRole { role: Role, address: String },
+
+    /// Returns the holders of `role`, paginated by address
+    #[returns(RoleMembersResponse)]
+    RoleMembers {
+        role: Role,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns which operations are paused
+    #[returns(PauseInfoResponse)]
+    PauseInfo {},
+
+    /// Returns the minter set at instantiate or by `UpdateMinter` with its cap, `None` without
+    /// one. Minters granted with `GrantRole` are listed by `RoleMembers`
+    #[returns(Option<MinterResponse>)]
+    Minter {},
+
+    /// Returns the project metadata and marketing admin
+    #[returns(MarketingInfoResponse)]
+    MarketingInfo {},
+
+    /// Returns the embedded logo, fails if the logo is a URL
+    #[returns(DownloadLogoResponse)]
+    DownloadLogo {},
+
+    /// Returns how much `spender` can use from `owner`'s account
+    #[returns(AllowanceResponse)]
+    Allowance { owner: String, spender: String },
+
+    /// Returns all allowances granted by `owner`, paginated by spender
+    #[returns(AllAllowancesResponse)]
+    AllAllowances {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns all allowances granted to `spender`, paginated by owner
+    #[returns(AllSpenderAllowancesResponse)]
+    AllSpenderAllowances {
+        spender: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns all accounts holding a balance, paginated by address
+    #[returns(AllAccountsResponse)]
+    AllAccounts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        /// Also lists accounts whose balance dropped to zero
+        include_zero_balances: Option<bool>,
+    },
 }
 
@@ -0,0 +1,402 @@
+use crate::helpers::{assert_transfer_allowed, quote_transfer, rate_limit_of, remaining_outflow};
+use crate::msg::{
+    AccruedFeesResponse, AllAccountsResponse, AllAllowancesResponse, AllSpenderAllowancesResponse,
+    AllowanceInfo, AllowanceResponse, BalanceResponse, ConfigResponse, DownloadLogoResponse,
+    EmbeddedLogo, FeeExemptionInfo, FeeExemptionsResponse, FeeRecipient, FeeScheduleResponse,
+    FeeShare, FeeTarget, FrozenAccountInfo, FrozenAccountsResponse, HasRoleResponse, Logo,
+    MarketingInfoResponse, MinterResponse, PauseInfoResponse, PendingFeeChangeResponse,
+    RateLimitResponse, RoleMembersResponse, ScheduledOperationResponse, ScheduledOperationsResponse,
+    SimulateTransferResponse, SpenderAllowanceInfo, TokenInfoResponse,
+};
+use crate::roles::{has_role, minter_data, role_members, Role};
+use crate::state::{
+    ACCRUED_FEES, ALLOWANCES, ALLOWANCES_SPENDER, BALANCES, FEE_EXEMPT, FROZEN, LOGO,
+    MARKETING_INFO, MINTER, PAUSED, PENDING_FEE_CHANGE, SCHEDULED_OPERATIONS, TOKEN_INFO,
+};
+use cosmwasm_std::{Addr, BlockInfo, Deps, Order, StdError, StdResult, Uint128};
+use cw_storage_plus::Bound;
+
+// Page sizes used by cw20-base
+pub const DEFAULT_LIMIT: u32 = 10;
+pub const MAX_LIMIT: u32 = 30;
+
+fn unvalidate_fee_recipients(recipients: Vec<FeeRecipient<Addr>>) -> Vec<FeeRecipient> {
+    recipients
+        .into_iter()
+        .map(|recipient| FeeRecipient {
+            target: match recipient.target {
+                FeeTarget::Address(addr) => FeeTarget::Address(addr.into_string()),
+                FeeTarget::Burn => FeeTarget::Burn,
+            },
+            weight: recipient.weight,
+        })
+        .collect()
+}
+
+pub fn query_token_info(deps: Deps) -> StdResult<TokenInfoResponse> {
+    let token_info = TOKEN_INFO.load(deps.storage)?;
+    Ok(TokenInfoResponse {
+        name: token_info.name,
+        symbol: token_info.symbol,
+        decimals: token_info.decimals,
+        total_supply: token_info.circulating_supply,
+    })
+}
+
+pub fn query_balance(deps: Deps, address: String) -> StdResult<BalanceResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+    let balance = BALANCES.may_load(deps.storage, &addr)?.unwrap_or_default();
+    Ok(BalanceResponse { balance })
+}
+
+pub fn query_fee_schedule(deps: Deps) -> StdResult<FeeScheduleResponse> {
+    let token_info = TOKEN_INFO.load(deps.storage)?;
+    Ok(FeeScheduleResponse {
+        fee_rate: token_info.fee_rate,
+        fee_tiers: token_info.fee_tiers,
+    })
+}
+
+pub fn query_simulate_transfer(
+    deps: Deps,
+    block: &BlockInfo,
+    sender: String,
+    recipient: String,
+    amount: Uint128,
+) -> StdResult<SimulateTransferResponse> {
+    let sender = deps.api.addr_validate(&sender)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    // Same checks and quote `Transfer` executes with, contract errors surface as query errors
+    let quote = assert_transfer_allowed(deps.storage, block, &sender, &recipient, amount)
+        .and_then(|_| quote_transfer(deps.storage, &sender, &recipient, amount))
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let fee_breakdown = quote
+        .fee_breakdown
+        .into_iter()
+        .map(|share| FeeShare {
+            target: match share.target {
+                FeeTarget::Address(addr) => FeeTarget::Address(addr.to_string()),
+                FeeTarget::Burn => FeeTarget::Burn,
+            },
+            amount: share.amount,
+        })
+        .collect();
+    Ok(SimulateTransferResponse {
+        net_amount: quote.net_amount,
+        fee: quote.fee,
+        fee_breakdown,
+        exempt: quote.exempt,
+    })
+}
+
+pub fn query_accrued_fees(deps: Deps, address: String) -> StdResult<AccruedFeesResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+    let amount = ACCRUED_FEES.may_load(deps.storage, &addr)?.unwrap_or_default();
+    Ok(AccruedFeesResponse { amount })
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let token_info = TOKEN_INFO.load(deps.storage)?;
+    // The cw-ownable record is the source of truth, it follows `UpdateOwnership`
+    let ownership = cw_ownable::get_ownership(deps.storage)?;
+    Ok(ConfigResponse {
+        owner: ownership.owner.map(|owner| owner.to_string()),
+        fee_recipients: unvalidate_fee_recipients(token_info.fee_recipients),
+        fee_rate: token_info.fee_rate,
+        max_fee_rate: token_info.max_fee_rate,
+        fee_rounding: token_info.fee_rounding,
+        mint_fee_rate: token_info.mint_fee_rate,
+        burn_fee_rate: token_info.burn_fee_rate,
+        min_fee: token_info.min_fee,
+        max_fee: token_info.max_fee,
+        fee_change_delay: token_info.fee_change_delay,
+        timelock_delay: token_info.timelock_delay,
+        max_supply: token_info.max_supply,
+    })
+}
+
+pub fn query_pending_fee_change(deps: Deps) -> StdResult<Option<PendingFeeChangeResponse>> {
+    let pending = PENDING_FEE_CHANGE
+        .may_load(deps.storage)?
+        .map(|pending| PendingFeeChangeResponse {
+            fee_rate: pending.fee_rate,
+            fee_recipients: unvalidate_fee_recipients(pending.fee_recipients),
+            fee_tiers: pending.fee_tiers,
+            ready_at: pending.ready_at,
+        });
+    Ok(pending)
+}
+
+pub fn query_rate_limit(
+    deps: Deps,
+    block: &BlockInfo,
+    address: String,
+) -> StdResult<RateLimitResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+    Ok(RateLimitResponse {
+        limit: rate_limit_of(deps.storage, &addr)?,
+        remaining: remaining_outflow(deps.storage, block, &addr)?,
+    })
+}
+
+pub fn query_scheduled_operation(deps: Deps, id: u64) -> StdResult<ScheduledOperationResponse> {
+    let operation = SCHEDULED_OPERATIONS.load(deps.storage, id)?;
+    Ok(ScheduledOperationResponse {
+        id,
+        msg: operation.msg,
+        proposer: operation.proposer.to_string(),
+        ready_at: operation.ready_at,
+    })
+}
+
+pub fn query_scheduled_operations(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ScheduledOperationsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let operations = SCHEDULED_OPERATIONS
+        .range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(id, operation)| ScheduledOperationResponse {
+                id,
+                msg: operation.msg,
+                proposer: operation.proposer.to_string(),
+                ready_at: operation.ready_at,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(ScheduledOperationsResponse { operations })
+}
+
+pub fn query_pause_info(deps: Deps) -> StdResult<PauseInfoResponse> {
+    Ok(PauseInfoResponse {
+        paused: PAUSED.may_load(deps.storage)?.unwrap_or_default(),
+    })
+}
+
+pub fn query_has_role(deps: Deps, role: Role, address: String) -> StdResult<HasRoleResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+    Ok(HasRoleResponse {
+        has_role: has_role(deps.storage, role, &addr),
+    })
+}
+
+pub fn query_role_members(
+    deps: Deps,
+    role: Role,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<RoleMembersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+
+    let members = role_members(deps.storage, role, start.as_ref(), limit)?
+        .into_iter()
+        .map(|addr| addr.to_string())
+        .collect();
+    Ok(RoleMembersResponse { members })
+}
+
+/// cw20 knows a single minter, the one set at instantiate or by `UpdateMinter` is reported
+pub fn query_minter(deps: Deps) -> StdResult<Option<MinterResponse>> {
+    let minter = match MINTER.may_load(deps.storage)? {
+        Some(minter) => minter_data(deps.storage, &minter)?,
+        None => None,
+    };
+    Ok(minter.map(|minter| MinterResponse {
+        minter: minter.minter.to_string(),
+        cap: minter.cap,
+    }))
+}
+
+pub fn query_marketing_info(deps: Deps) -> StdResult<MarketingInfoResponse> {
+    Ok(MARKETING_INFO.may_load(deps.storage)?.unwrap_or_default())
+}
+
+pub fn query_download_logo(deps: Deps) -> StdResult<DownloadLogoResponse> {
+    let logo = LOGO.load(deps.storage)?;
+    match logo {
+        Logo::Embedded(EmbeddedLogo::Svg(logo)) => Ok(DownloadLogoResponse {
+            mime_type: "image/svg+xml".to_string(),
+            data: logo,
+        }),
+        Logo::Embedded(EmbeddedLogo::Png(logo)) => Ok(DownloadLogoResponse {
+            mime_type: "image/png".to_string(),
+            data: logo,
+        }),
+        Logo::Url(_) => Err(StdError::not_found("embedded logo")),
+    }
+}
+
+pub fn query_allowance(deps: Deps, owner: String, spender: String) -> StdResult<AllowanceResponse> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let spender_addr = deps.api.addr_validate(&spender)?;
+
+    let allowance = ALLOWANCES
+        .may_load(deps.storage, (&owner_addr, &spender_addr))?
+        .unwrap_or_default();
+
+    Ok(AllowanceResponse {
+        allowance: allowance.amount,
+        expires: allowance.expires,
+    })
+}
+
+pub fn query_all_allowances(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllAllowancesResponse> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+
+    let allowances = ALLOWANCES
+        .prefix(&owner_addr)
+        .range(
+            deps.storage,
+            start.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            item.map(|(spender, allowance)| AllowanceInfo {
+                spender: spender.to_string(),
+                allowance: allowance.amount,
+                expires: allowance.expires,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(AllAllowancesResponse { allowances })
+}
+
+pub fn query_all_spender_allowances(
+    deps: Deps,
+    spender: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllSpenderAllowancesResponse> {
+    let spender_addr = deps.api.addr_validate(&spender)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+
+    let allowances = ALLOWANCES_SPENDER
+        .prefix(&spender_addr)
+        .range(
+            deps.storage,
+            start.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            item.map(|(owner, allowance)| SpenderAllowanceInfo {
+                owner: owner.to_string(),
+                allowance: allowance.amount,
+                expires: allowance.expires,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(AllSpenderAllowancesResponse { allowances })
+}
+
+pub fn query_all_accounts(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    include_zero_balances: bool,
+) -> StdResult<AllAccountsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+
+    // Zero balances are filtered before `take` so a page is never short
+    let accounts = BALANCES
+        .range(
+            deps.storage,
+            start.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .filter(|item| {
+            include_zero_balances || item.as_ref().map_or(true, |(_, balance)| !balance.is_zero())
+        })
+        .take(limit)
+        .map(|item| item.map(|(addr, _)| addr.to_string()))
+        .collect::<StdResult<_>>()?;
+
+    Ok(AllAccountsResponse { accounts })
+}
+
+pub fn query_fee_exemptions(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<FeeExemptionsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+
+    let exemptions = FEE_EXEMPT
+        .range(
+            deps.storage,
+            start.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            item.map(|(address, kind)| FeeExemptionInfo {
+                address: address.to_string(),
+                kind,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(FeeExemptionsResponse { exemptions })
+}
+
+pub fn query_frozen_accounts(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<FrozenAccountsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+
+    let accounts = FROZEN
+        .range(
+            deps.storage,
+            start.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            item.map(|(address, info)| FrozenAccountInfo {
+                address: address.to_string(),
+                reason: info.reason,
+                frozen_at: info.frozen_at,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(FrozenAccountsResponse { accounts })
+}
@@ -0,0 +1,122 @@
+// @dev Role based access control. The cw-ownable owner grants and revokes roles,
+// each role unlocks one group of privileged messages.
+
+use cosmwasm_std::{Addr, Empty, Order, StdResult, Storage};
+use cw_storage_plus::{Bound, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::error::ContractError;
+use crate::state::{MinterData, MINTER, MINTER_CAPS};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Minter,            // Mints up to the mint cap
+    Pauser,            // Pauses and resumes operations
+    FeeManager,        // Changes fees and fee exemptions
+    ComplianceOfficer, // Freezes and unfreezes accounts
+}
+
+impl Role {
+    pub const ALL: [Role; 4] = [
+        Role::Minter,
+        Role::Pauser,
+        Role::FeeManager,
+        Role::ComplianceOfficer,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Minter => "minter",
+            Role::Pauser => "pauser",
+            Role::FeeManager => "fee_manager",
+            Role::ComplianceOfficer => "compliance_officer",
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+// Role holders keyed by (role, address), so the holders of a role can be listed
+pub const ROLES: Map<(&str, &Addr), Empty> = Map::new("roles");
+
+pub fn has_role(storage: &dyn Storage, role: Role, addr: &Addr) -> bool {
+    ROLES.has(storage, (role.as_str(), addr))
+}
+
+/// Fails with `Unauthorized` unless `addr` holds `role`
+pub fn assert_role(storage: &dyn Storage, role: Role, addr: &Addr) -> Result<(), ContractError> {
+    if !has_role(storage, role, addr) {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+pub fn grant_role(storage: &mut dyn Storage, role: Role, addr: &Addr) -> StdResult<()> {
+    ROLES.save(storage, (role.as_str(), addr), &Empty {})
+}
+
+/// Drops `role` from `addr`, along with its mint cap for the minter role
+pub fn revoke_role(storage: &mut dyn Storage, role: Role, addr: &Addr) {
+    ROLES.remove(storage, (role.as_str(), addr));
+    if role == Role::Minter {
+        MINTER_CAPS.remove(storage, addr);
+    }
+}
+
+/// Hands every role of `from` to `to`, or drops them when `to` is `None`.
+/// Roles `to` already holds keep their own settings
+pub fn transfer_roles(storage: &mut dyn Storage, from: &Addr, to: Option<&Addr>) -> StdResult<()> {
+    let cap = MINTER_CAPS.may_load(storage, from)?;
+    for role in Role::ALL {
+        if !has_role(storage, role, from) {
+            continue;
+        }
+        revoke_role(storage, role, from);
+        if let Some(to) = to.filter(|to| !has_role(storage, role, to)) {
+            grant_role(storage, role, to)?;
+            if let (Role::Minter, Some(cap)) = (role, cap) {
+                MINTER_CAPS.save(storage, to, &cap)?;
+            }
+        }
+    }
+
+    if MINTER.may_load(storage)?.as_ref() == Some(from) {
+        match to {
+            Some(to) => MINTER.save(storage, to)?,
+            None => MINTER.remove(storage),
+        }
+    }
+    Ok(())
+}
+
+/// Minter role and cap of `addr`, `None` unless it holds the minter role
+pub fn minter_data(storage: &dyn Storage, addr: &Addr) -> StdResult<Option<MinterData>> {
+    if !has_role(storage, Role::Minter, addr) {
+        return Ok(None);
+    }
+    Ok(Some(MinterData {
+        minter: addr.clone(),
+        cap: MINTER_CAPS.may_load(storage, addr)?,
+    }))
+}
+
+/// Holders of `role` in address order, starting after `start_after`
+pub fn role_members(
+    storage: &dyn Storage,
+    role: Role,
+    start_after: Option<&Addr>,
+    limit: usize,
+) -> StdResult<Vec<Addr>> {
+    ROLES
+        .prefix(role.as_str())
+        .keys(storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
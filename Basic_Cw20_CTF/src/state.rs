@@ -4,30 +4,221 @@ use schemars::JsonSchema; //schemars is a Rust crate that generates JSON schemas
 
 use serde::{Deserialize, Serialize}; //	Serialize and Deserialize derive macros allow the State struct to be converted to/from a format that can be stored on the blockchain.
 
-use cosmwasm_std::{Addr, Decimal}; //Addr is a type provided by CosmWasm to represent validated blockchain addresses. Unlike simple strings, Addr ensures that the address conforms to the blockchain’s address format.
+use cosmwasm_std::{Addr, Binary, Decimal, Timestamp, Uint128}; //Addr is a type provided by CosmWasm to represent validated blockchain addresses. Unlike simple strings, Addr ensures that the address conforms to the blockchain’s address format.
 
 use cw_storage_plus::{Item, Map};
+use cw_utils::{Duration, Expiration};
+use std::fmt;
+
+use crate::msg::{FeeRecipient, FeeRounding, FeeTier, Logo, MarketingInfoResponse};
 // cw-storage-plus is a CosmWasm helper crate for working with persistent storage. Item is a high-level abstraction for a single piece of data stored on the blockchain.
 
+// Maximum fee rate when none is configured at instantiate
+pub const DEFAULT_MAX_FEE_RATE: Decimal = Decimal::percent(3);
+
+// Notice period for fee changes when none is configured at instantiate, one day
+pub const DEFAULT_FEE_CHANGE_DELAY: Duration = Duration::Time(24 * 60 * 60);
+
+// Wait before a scheduled operation can run when none is configured at instantiate, two days
+pub const DEFAULT_TIMELOCK_DELAY: Duration = Duration::Time(2 * 24 * 60 * 60);
+
 // Store Token MetaData
 pub const TOKEN_INFO: Item<TokenInfo> = Item::new("token_info");
 
 //Storing Account Balances Mapping
-pub const BALANCES: Map<&Addr, u128> = Map::new("balances");
+pub const BALANCES: Map<&Addr, Uint128> = Map::new("balances");
 
-//ALlowances
-pub const ALLOWANCES: Map<(&Addr, &Addr), u128> = Map::new("allowances");
+//ALlowances keyed by (owner, spender)
+pub const ALLOWANCES: Map<(&Addr, &Addr), AllowanceInfo> = Map::new("allowances");
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+//Same allowances keyed by (spender, owner) so they can be listed per spender
+pub const ALLOWANCES_SPENDER: Map<(&Addr, &Addr), AllowanceInfo> =
+    Map::new("allowances_spender");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct TokenInfo {
     pub name: String,
     pub symbol: String,
     pub decimals: u8,
-    pub circulating_supply: u128,
-    pub max_supply: u128,
-    pub owner: Addr,         // Owner of the token
-    pub fee_collector: Addr, // Address to collect fees
+    pub circulating_supply: Uint128,
+    pub max_supply: Uint128,
+    pub fee_recipients: Vec<FeeRecipient<Addr>>, // Weighted split of collected fees
+    pub fee_rate: Decimal,
+    pub fee_tiers: Vec<FeeTier<Decimal>>, // Sorted by threshold, override fee_rate once reached
+    pub max_fee_rate: Decimal, // Bound checked whenever the fee rate is set
+    pub fee_rounding: FeeRounding,
+    pub mint_fee_rate: Option<Decimal>, // Share of each mint paid to the fee recipients
+    pub burn_fee_rate: Option<Decimal>, // Share of each burn paid to the fee recipients
+    pub min_fee: Option<Uint128>, // Absolute floor of the fee charged per transfer
+    pub max_fee: Option<Uint128>, // Absolute cap of the fee charged per transfer
+    pub fee_change_delay: Duration, // Wait between proposing and applying a fee change
+    pub timelock_delay: Duration, // Wait between scheduling and executing an operation
+}
+
+// Fee change waiting out its delay, at most one at a time
+pub const PENDING_FEE_CHANGE: Item<PendingFeeChange> = Item::new("pending_fee_change");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingFeeChange {
     pub fee_rate: Decimal,
+    pub fee_recipients: Vec<FeeRecipient<Addr>>,
+    pub fee_tiers: Vec<FeeTier<Decimal>>,
+    pub ready_at: Expiration,
+}
+
+// Owner operations queued behind the timelock, keyed by an increasing id
+pub const SCHEDULED_OPERATIONS: Map<u64, ScheduledOperation> = Map::new("scheduled_operations");
+
+// Id given to the next scheduled operation
+pub const NEXT_OPERATION_ID: Item<u64> = Item::new("next_operation_id");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScheduledOperation {
+    pub msg: Binary, // ExecuteMsg as JSON, decoded when the operation runs
+    pub proposer: Addr, // Runs the message, must still be the owner by then
+    pub ready_at: Expiration,
+}
+
+// Project metadata shown by wallets and explorers
+pub const MARKETING_INFO: Item<MarketingInfoResponse> = Item::new("marketing_info");
+
+// Logo as uploaded, URL or embedded image
+pub const LOGO: Item<Logo> = Item::new("logo");
+
+// Fees credited to each fee recipient and not yet claimed into its balance.
+// These tokens are still part of the circulating supply
+pub const ACCRUED_FEES: Map<&Addr, Uint128> = Map::new("accrued_fees");
+
+// Accounts that move tokens without paying the transfer fee
+pub const FEE_EXEMPT: Map<&Addr, ExemptionKind> = Map::new("fee_exempt");
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExemptionKind {
+    Sender,    // No fee when the account sends
+    Recipient, // No fee when the account receives
+    Both,
+}
+
+impl ExemptionKind {
+    pub fn covers_sender(&self) -> bool {
+        matches!(self, ExemptionKind::Sender | ExemptionKind::Both)
+    }
+
+    pub fn covers_recipient(&self) -> bool {
+        matches!(self, ExemptionKind::Recipient | ExemptionKind::Both)
+    }
+}
+
+// Accounts whose funds cannot move, e.g. sanctioned addresses
+pub const FROZEN: Map<&Addr, FreezeInfo> = Map::new("frozen");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct FreezeInfo {
+    pub reason: String,
+    pub frozen_at: Timestamp,
+}
+
+// Operations halted by the pauser, nothing is paused when unset
+pub const PAUSED: Item<PausedOperations> = Item::new("paused");
+
+/// Groups of execute messages that can be paused independently
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    Transfer,  // Transfers, sends and fee claims
+    Mint,
+    Burn,      // Burns, also through an allowance
+    Allowance, // Allowance increases and decreases
+}
+
+impl Operation {
+    pub const ALL: [Operation; 4] = [
+        Operation::Transfer,
+        Operation::Mint,
+        Operation::Burn,
+        Operation::Allowance,
+    ];
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operation::Transfer => write!(f, "transfer"),
+            Operation::Mint => write!(f, "mint"),
+            Operation::Burn => write!(f, "burn"),
+            Operation::Allowance => write!(f, "allowance"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct PausedOperations {
+    pub transfer: bool,
+    pub mint: bool,
+    pub burn: bool,
+    pub allowance: bool,
+}
+
+impl PausedOperations {
+    pub fn is_paused(&self, operation: Operation) -> bool {
+        match operation {
+            Operation::Transfer => self.transfer,
+            Operation::Mint => self.mint,
+            Operation::Burn => self.burn,
+            Operation::Allowance => self.allowance,
+        }
+    }
+
+    pub fn set(&mut self, operation: Operation, paused: bool) {
+        match operation {
+            Operation::Transfer => self.transfer = paused,
+            Operation::Mint => self.mint = paused,
+            Operation::Burn => self.burn = paused,
+            Operation::Allowance => self.allowance = paused,
+        }
+    }
+}
+
+// Outflow limit applied to accounts without a limit of their own, unlimited when unset
+pub const GLOBAL_RATE_LIMIT: Item<RateLimit> = Item::new("global_rate_limit");
+
+// Per account outflow limits set by the owner, taking precedence over the global one
+pub const RATE_LIMITS: Map<&Addr, RateLimit> = Map::new("rate_limits");
+
+// Outflows still inside the window of each limited account, summed per bucket of the
+// window, oldest first. Only recorded while a limit applies to the account
+pub const OUTFLOWS: Map<&Addr, Vec<Outflow>> = Map::new("outflows");
+
+/// At most `amount` tokens leave an account within any `window` seconds, a year at most
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RateLimit {
+    pub amount: Uint128,
+    pub window: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Outflow {
+    pub at: Timestamp, // Start of the bucket
+    pub amount: Uint128,
+}
+
+// Minter set at instantiate or by `UpdateMinter`, the one reported to cw20 clients
+pub const MINTER: Item<Addr> = Item::new("minter");
+
+// Mint cap of each minter, a minter without an entry is only bound by the max supply
+pub const MINTER_CAPS: Map<&Addr, Uint128> = Map::new("minter_caps");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MinterData {
+    pub minter: Addr,
+    pub cap: Option<Uint128>, // Ceiling on circulating supply this minter can mint up to
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct AllowanceInfo {
+    pub amount: Uint128,
+    pub expires: Expiration, // Defaults to `Expiration::Never`
 }
 
 // // What’s Happening Here:
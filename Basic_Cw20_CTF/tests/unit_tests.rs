@@ -1,18 +1,34 @@
 use cosmwasm_std::{
     testing::*,
-    Addr,
+    coins, to_json_binary, Addr, Binary, Decimal, Env, MessageInfo, Response, Uint128,
 };
 use cw_multi_test::IntoAddr;
+use cw_utils::{Duration, Expiration};
+use proptest::prelude::*;
 use Basic_CW_20::{
-    contract::{instantiate, query, execute},
-    msg::{InstantiateMsg, ExecuteMsg, QueryMsg},
+    contract::{instantiate, migrate, query, execute, CONTRACT_NAME, CONTRACT_VERSION},
+    msg::{
+        AccruedFeesResponse, AllAccountsResponse, AllAllowancesResponse, AllSpenderAllowancesResponse, AllowanceResponse,
+        BalanceResponse, ConfigResponse, Cw20ReceiveMsg, DownloadLogoResponse, EmbeddedLogo,
+        FeeExemptionInfo, FeeExemptionsResponse, FeeRate, FeeRecipient, FeeRounding,
+        FeeScheduleResponse, FeeShare, FeeTarget, FeeTier,
+        FrozenAccountInfo, FrozenAccountsResponse, HasRoleResponse, InstantiateMarketingInfo,
+        Logo, LogoInfo, MarketingInfoResponse, TokenInfoResponse,
+        InstantiateMsg, ExecuteMsg, MigrateMsg, MinterResponse, PauseInfoResponse,
+        PendingFeeChangeResponse, QueryMsg, RateLimitResponse, RoleMembersResponse,
+        ScheduledOperationsResponse,
+        SimulateTransferResponse,
+    },
     error::ContractError,
-    state::*,
+    helpers::calculate_fee,
+    migrations::v0_1,
+    roles::Role,
+    state::{ExemptionKind, Operation, PausedOperations, RateLimit, OUTFLOWS},
 };
-    use cosmwasm_std::{from_json, OwnedDeps};
+    use cosmwasm_std::{from_json, OwnedDeps, Timestamp};
 
-    const INITIAL_SUPPLY: u128 = 1_000_000;
-    const MAX_SUPPLY: u128 = 2_000_000;
+    const INITIAL_SUPPLY: Uint128 = Uint128::new(1_000_000);
+    const MAX_SUPPLY: Uint128 = Uint128::new(2_000_000);
     struct TestAddresses {
         owner: Addr,
         user1: Addr,
@@ -30,32 +46,88 @@ use Basic_CW_20::{
             fee_collector : "fee_collector".into_addr(),
         };
 
-        let msg = InstantiateMsg {
+        let msg = base_instantiate_msg(&addresses.owner, &addresses.fee_collector);
+
+        let info = message_info(&addresses.owner, &[]);
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        (deps, addresses)
+    }
+
+    // Plain 1% fee token, tests override the fields they exercise
+    fn base_instantiate_msg(owner: &Addr, fee_collector: &Addr) -> InstantiateMsg {
+        InstantiateMsg {
             name: "Test Token".to_string(),
             symbol: "TEST".to_string(),
             decimals: 6,
             initial_supply: INITIAL_SUPPLY,
             max_supply: MAX_SUPPLY,
-            owner: addresses.owner.to_string(),
-            fee_collector: addresses.fee_collector.to_string(),
-            fee_rate: 1, // 1% fee
-        };
-
-        let info = mock_info(addresses.owner.as_str(), &[]);
-        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
+            owner: owner.to_string(),
+            fee_recipients: single_recipient(fee_collector),
+            fee_rate: FeeRate::Bps(100), // 1% fee
+            fee_tiers: vec![],
+            max_fee_rate: None,
+            fee_rounding: None,
+            mint_fee_rate: None,
+            burn_fee_rate: None,
+            min_fee: None,
+            max_fee: None,
+            fee_change_delay: None,
+            timelock_delay: None,
+            mint: None,
+            pauser: None,
+            marketing: None,
+        }
+    }
 
-        (deps, addresses)
+    fn single_recipient(addr: &Addr) -> Vec<FeeRecipient> {
+        vec![FeeRecipient { target: FeeTarget::Address(addr.to_string()), weight: 1 }]
     }
 
-    fn query_balance(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, address: &Addr) -> u128 {
+    fn query_balance(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, address: &Addr) -> Uint128 {
         let res = query(
             deps.as_ref(),
             mock_env(),
             QueryMsg::Balance { address: address.to_string() },
         )
         .unwrap();
-        from_json(&res).unwrap()
+        from_json::<BalanceResponse>(&res).unwrap().balance
+    }
+
+    fn query_accrued_fees(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, address: &Addr) -> Uint128 {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AccruedFees { address: address.to_string() },
+        )
+        .unwrap();
+        from_json::<AccruedFeesResponse>(&res).unwrap().amount
+    }
+
+    // Owner actions have to wait out the timelock, scheduled at `env` and run once ready
+    fn execute_timelocked(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        env: &Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        let schedule = ExecuteMsg::ScheduleOperation { msg: Box::new(msg) };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), schedule)?;
+        let id = res.attributes.iter().find(|attr| attr.key == "id").unwrap();
+        let id = id.value.parse().unwrap();
+        let mut env = env.clone();
+        env.block.time = env.block.time.plus_days(2);
+        execute(deps.as_mut(), env, info, ExecuteMsg::ExecuteOperation { id })
+    }
+
+    fn query_rate_limit(
+        deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        env: &Env,
+        address: &Addr,
+    ) -> RateLimitResponse {
+        let msg = QueryMsg::RateLimit { address: address.to_string() };
+        from_json(query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap()
     }
 
     #[test]
@@ -63,17 +135,21 @@ use Basic_CW_20::{
         let (deps, addresses) = setup_contract();
         
         let res = query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap();
-        let token_info: TokenInfo = from_json(&res).unwrap();
+        let token_info: TokenInfoResponse = from_json(&res).unwrap();
         
         assert_eq!(token_info.name, "Test Token");
         assert_eq!(token_info.symbol, "TEST");
         assert_eq!(token_info.decimals, 6);
-        assert_eq!(token_info.circulating_supply, INITIAL_SUPPLY);
-        assert_eq!(token_info.max_supply, MAX_SUPPLY);
-        assert_eq!(token_info.owner, addresses.owner);
-        assert_eq!(token_info.fee_collector, addresses.fee_collector);
+        assert_eq!(token_info.total_supply, INITIAL_SUPPLY);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let config: ConfigResponse = from_json(&res).unwrap();
+        assert_eq!(config.max_supply, MAX_SUPPLY);
+        assert_eq!(config.owner, Some(addresses.owner.to_string()));
+        assert_eq!(config.fee_recipients, single_recipient(&addresses.fee_collector));
+        assert_eq!(config.fee_rate, Decimal::percent(1));
         
-        let balance: u128 = query_balance(&deps, &addresses.owner);
+        let balance: Uint128 = query_balance(&deps, &addresses.owner);
         assert_eq!(balance, INITIAL_SUPPLY);
     }
 
@@ -81,12 +157,12 @@ use Basic_CW_20::{
     #[test]
     fn test_transfer() {
         let (mut deps, addresses) = setup_contract();
-        let balance: u128 = query_balance(&deps, &addresses.owner);
+        let balance: Uint128 = query_balance(&deps, &addresses.owner);
         assert_eq!(balance, INITIAL_SUPPLY);
 
-        let transfer_amount = 100_000u128;
+        let transfer_amount = Uint128::new(100_000);
 
-        let info = mock_info(addresses.owner.as_str(), &[]);
+        let info = message_info(&addresses.owner, &[]);
         let msg = ExecuteMsg::Transfer {
             recipient: addresses.user1.to_string(),
             amount: transfer_amount,
@@ -95,75 +171,76 @@ use Basic_CW_20::{
         assert_eq!(res.attributes.len(), 5);
 
         // Check recipient balance (amount - 1% fee)
-        let balance: u128 = query_balance(&deps, &addresses.user1);
-        assert_eq!(balance, 99_000);
+        let balance: Uint128 = query_balance(&deps, &addresses.user1);
+        assert_eq!(balance, Uint128::new(99_000));
 
-        // Check fee collector balance
-        let fee_balance: u128 = query_balance(&deps, &addresses.fee_collector);
-        assert_eq!(fee_balance, 1_000);
+        // Check fees accrued to the collector
+        let fee_balance: Uint128 = query_accrued_fees(&deps, &addresses.fee_collector);
+        assert_eq!(fee_balance, Uint128::new(1_000));
     }
 
     #[test]
     fn test_mint() {
         let (mut deps, addresses) = setup_contract();
-        let mint_amount = 500_000u128;
+        let mint_amount = Uint128::new(500_000);
 
-        let info = mock_info(addresses.owner.as_str(), &[]);
+        let info = message_info(&addresses.owner, &[]);
         let msg = ExecuteMsg::Mint {
             recipient: addresses.user1.to_string(),
             amount: mint_amount,
         };
-        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(res.attributes.len(), 3);
+        let res = execute_timelocked(&mut deps, &mock_env(), info, msg).unwrap();
+        assert_eq!(res.attributes.len(), 4);
 
         // Verify recipient balance
-        let balance: u128 = query_balance(&deps, &addresses.user1);
+        let balance: Uint128 = query_balance(&deps, &addresses.user1);
         assert_eq!(balance, mint_amount);
 
         // Verify updated total supply
         let res = query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap();
-        let token_info: TokenInfo = from_json(&res).unwrap();
-        assert_eq!(token_info.circulating_supply, INITIAL_SUPPLY + mint_amount);
+        let token_info: TokenInfoResponse = from_json(&res).unwrap();
+        assert_eq!(token_info.total_supply, INITIAL_SUPPLY + mint_amount);
     }
 
     #[test]
     fn test_burn() {
         let (mut deps, addresses) = setup_contract();
-        let burn_amount = 100_000u128;
+        let burn_amount = Uint128::new(100_000);
 
-        let info = mock_info(addresses.owner.as_str(), &[]);
+        let info = message_info(&addresses.owner, &[]);
         let msg = ExecuteMsg::Burn { amount: burn_amount };
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(res.attributes.len(), 3);
+        assert_eq!(res.attributes.len(), 4);
 
         // Verify updated balance
-        let balance: u128 = query_balance(&deps, &addresses.owner);
+        let balance: Uint128 = query_balance(&deps, &addresses.owner);
         assert_eq!(balance, INITIAL_SUPPLY - burn_amount);
 
         // Verify updated total supply
         let res = query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap();
-        let token_info: TokenInfo = from_json(&res).unwrap();
-        assert_eq!(token_info.circulating_supply, INITIAL_SUPPLY - burn_amount);
+        let token_info: TokenInfoResponse = from_json(&res).unwrap();
+        assert_eq!(token_info.total_supply, INITIAL_SUPPLY - burn_amount);
     }
 
     #[test]
     fn test_allowances_and_transfer_from() {
         let (mut deps, addresses) = setup_contract();
-        let allowance_amount = 100_000u128;
+        let allowance_amount = Uint128::new(100_000);
 
         // Owner increases allowance for user1
-        let info = mock_info(addresses.owner.as_str(), &[]);
+        let info = message_info(&addresses.owner, &[]);
         let msg = ExecuteMsg::IncreaseAllowance {
             owner: addresses.owner.to_string(),
             spender:addresses.user1.to_string(),
             amount: allowance_amount,
+            expires: None,
         };
         let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
         assert_eq!(res.attributes.len(), 4);
 
         // User1 transfers from owner to user2
-        let transfer_amount = 50_000u128;
-        let info = mock_info(addresses.user1.as_str(), &[]);
+        let transfer_amount = Uint128::new(50_000);
+        let info = message_info(&addresses.user1, &[]);
 
         let msg = ExecuteMsg::TransferFrom {
             owner: addresses.owner.to_string(),
@@ -174,16 +251,16 @@ use Basic_CW_20::{
         assert_eq!(res.attributes.len(), 6);
 
         // Verify user2 balance (amount - 1% fee)
-        let balance: u128 = query_balance(&deps, &addresses.user2);
-        assert_eq!(balance, 49_500); // 50_000 - 1% fee
+        let balance: Uint128 = query_balance(&deps, &addresses.user2);
+        assert_eq!(balance, Uint128::new(49_500)); // 50_000 - 1% fee
     }
 
     #[test]
     fn test_insufficient_funds() {
         let (mut deps, addresses) = setup_contract();
-        let excess_amount = INITIAL_SUPPLY + 1;
+        let excess_amount = INITIAL_SUPPLY + Uint128::one();
 
-        let info = mock_info(addresses.owner.as_str(), &[]);
+        let info = message_info(&addresses.owner, &[]);
         let msg = ExecuteMsg::Transfer {
             recipient: addresses.user1.to_string(),
             amount: excess_amount,
@@ -195,15 +272,1473 @@ use Basic_CW_20::{
     #[test]
     fn test_max_supply_mint() {
         let (mut deps, addresses) = setup_contract();
-        let excess_mint = MAX_SUPPLY - INITIAL_SUPPLY + 1;
+        let excess_mint = MAX_SUPPLY - INITIAL_SUPPLY + Uint128::one();
 
-        let info = mock_info(addresses.owner.as_str(), &[]);
+        let info = message_info(&addresses.owner, &[]);
         let msg = ExecuteMsg::Mint {
             recipient: addresses.user1.to_string(),
             amount: excess_mint,
         };
-        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        let err = execute_timelocked(&mut deps, &mock_env(), info, msg).unwrap_err();
         assert!(matches!(err, ContractError::MaxSupplyReached { max_supply: _ }));
     }
 
-  
\ No newline at end of file
+  
+    #[test]
+    fn test_send_calls_receiver_with_net_amount() {
+        let (mut deps, addresses) = setup_contract();
+        let receiver = "receiver_contract".into_addr();
+        let send_amount = Uint128::new(100_000);
+        let payload = to_json_binary(&"stake").unwrap();
+
+        let info = message_info(&addresses.owner, &[]);
+        let msg = ExecuteMsg::Send {
+            contract: receiver.to_string(),
+            amount: send_amount,
+            msg: payload.clone(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.attributes.len(), 5);
+
+        // Receiver is notified of the amount after the 1% fee
+        let expected = Cw20ReceiveMsg {
+            sender: addresses.owner.to_string(),
+            amount: Uint128::new(99_000),
+            msg: payload,
+        }
+        .into_cosmos_msg(&receiver)
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.messages[0].msg, expected);
+
+        assert_eq!(query_balance(&deps, &receiver), Uint128::new(99_000));
+        assert_eq!(query_accrued_fees(&deps, &addresses.fee_collector), Uint128::new(1_000));
+        assert_eq!(query_balance(&deps, &addresses.owner), INITIAL_SUPPLY - send_amount);
+    }
+
+    #[test]
+    fn test_burn_from_consumes_allowance() {
+        let (mut deps, addresses) = setup_contract();
+
+        let info = message_info(&addresses.owner, &[]);
+        let msg = ExecuteMsg::IncreaseAllowance {
+            owner: addresses.owner.to_string(),
+            spender: addresses.user1.to_string(),
+            amount: Uint128::new(30_000),
+            expires: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = message_info(&addresses.user1, &[]);
+        let msg = ExecuteMsg::BurnFrom {
+            owner: addresses.owner.to_string(),
+            amount: Uint128::new(20_000),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(res.attributes.len(), 5);
+
+        assert_eq!(query_balance(&deps, &addresses.owner), INITIAL_SUPPLY - Uint128::new(20_000));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap();
+        let token_info: TokenInfoResponse = from_json(&res).unwrap();
+        assert_eq!(token_info.total_supply, INITIAL_SUPPLY - Uint128::new(20_000));
+
+        // Only 10_000 of the allowance is left
+        let msg = ExecuteMsg::BurnFrom {
+            owner: addresses.owner.to_string(),
+            amount: Uint128::new(20_000),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InsufficientAllowance { allowance, required }
+                if allowance == Uint128::new(10_000) && required == Uint128::new(20_000)
+        ));
+    }
+
+    #[test]
+    fn test_send_from_requires_allowance() {
+        let (mut deps, addresses) = setup_contract();
+        let receiver = "receiver_contract".into_addr();
+
+        let info = message_info(&addresses.user1, &[]);
+        let msg = ExecuteMsg::SendFrom {
+            owner: addresses.owner.to_string(),
+            contract: receiver.to_string(),
+            amount: Uint128::new(10_000),
+            msg: to_json_binary(&"stake").unwrap(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientAllowance { .. }));
+
+        let owner_info = message_info(&addresses.owner, &[]);
+        let allow = ExecuteMsg::IncreaseAllowance {
+            owner: addresses.owner.to_string(),
+            spender: addresses.user1.to_string(),
+            amount: Uint128::new(10_000),
+            expires: None,
+        };
+        execute(deps.as_mut(), mock_env(), owner_info, allow).unwrap();
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(query_balance(&deps, &receiver), Uint128::new(9_900));
+        assert_eq!(query_accrued_fees(&deps, &addresses.fee_collector), Uint128::new(100));
+    }
+
+    #[test]
+    fn test_expired_allowance_rejected() {
+        let (mut deps, addresses) = setup_contract();
+        let mut env = mock_env();
+        let expires = Expiration::AtHeight(env.block.height + 10);
+
+        let info = message_info(&addresses.owner, &[]);
+        let msg = ExecuteMsg::IncreaseAllowance {
+            owner: addresses.owner.to_string(),
+            spender: addresses.user1.to_string(),
+            amount: Uint128::new(10_000),
+            expires: Some(expires),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // An expiration in the past cannot be set
+        let msg = ExecuteMsg::IncreaseAllowance {
+            owner: addresses.owner.to_string(),
+            spender: addresses.user1.to_string(),
+            amount: Uint128::new(10_000),
+            expires: Some(Expiration::AtHeight(env.block.height - 1)),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidExpiration {}));
+
+        let transfer = ExecuteMsg::TransferFrom {
+            owner: addresses.owner.to_string(),
+            recipient: addresses.user2.to_string(),
+            amount: Uint128::new(5_000),
+        };
+        let info = message_info(&addresses.user1, &[]);
+        execute(deps.as_mut(), env.clone(), info.clone(), transfer.clone()).unwrap();
+
+        env.block.height += 10;
+        let err = execute(deps.as_mut(), env, info, transfer).unwrap_err();
+        assert!(matches!(err, ContractError::Expired {}));
+    }
+
+    #[test]
+    fn test_allowance_queries() {
+        let (mut deps, addresses) = setup_contract();
+
+        let grants = [
+            (&addresses.owner, &addresses.user1, Uint128::new(1_000)),
+            (&addresses.owner, &addresses.user2, Uint128::new(2_000)),
+            (&addresses.user1, &addresses.user2, Uint128::new(3_000)),
+        ];
+        for (owner, spender, amount) in grants {
+            let msg = ExecuteMsg::IncreaseAllowance {
+                owner: owner.to_string(),
+                spender: spender.to_string(),
+                amount,
+                expires: None,
+            };
+            execute(deps.as_mut(), mock_env(), message_info(owner, &[]), msg).unwrap();
+        }
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Allowance {
+                owner: addresses.owner.to_string(),
+                spender: addresses.user2.to_string(),
+            },
+        )
+        .unwrap();
+        let allowance: AllowanceResponse = from_json(&res).unwrap();
+        assert_eq!(allowance.allowance, Uint128::new(2_000));
+        assert_eq!(allowance.expires, Expiration::Never {});
+
+        // Page through the owner's allowances one at a time
+        let mut spenders = vec![];
+        let mut start_after = None;
+        loop {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::AllAllowances {
+                    owner: addresses.owner.to_string(),
+                    start_after: start_after.clone(),
+                    limit: Some(1),
+                },
+            )
+            .unwrap();
+            let page: AllAllowancesResponse = from_json(&res).unwrap();
+            match page.allowances.last() {
+                Some(last) => start_after = Some(last.spender.clone()),
+                None => break,
+            }
+            spenders.extend(page.allowances.into_iter().map(|a| a.spender));
+        }
+        spenders.sort();
+        let mut expected = vec![addresses.user1.to_string(), addresses.user2.to_string()];
+        expected.sort();
+        assert_eq!(spenders, expected);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AllSpenderAllowances {
+                spender: addresses.user2.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let by_spender: AllSpenderAllowancesResponse = from_json(&res).unwrap();
+        let mut amounts: Vec<Uint128> = by_spender.allowances.iter().map(|a| a.allowance).collect();
+        amounts.sort();
+        assert_eq!(amounts, vec![Uint128::new(2_000), Uint128::new(3_000)]);
+    }
+
+    #[test]
+    fn test_all_accounts() {
+        let (mut deps, addresses) = setup_contract();
+
+        // user1 receives tokens and then burns all of them
+        let info = message_info(&addresses.owner, &[]);
+        let msg = ExecuteMsg::Mint {
+            recipient: addresses.user1.to_string(),
+            amount: Uint128::new(1_000),
+        };
+        execute_timelocked(&mut deps, &mock_env(), info, msg).unwrap();
+        let info = message_info(&addresses.user1, &[]);
+        let msg = ExecuteMsg::Burn { amount: Uint128::new(1_000) };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let all_accounts = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                            start_after: Option<String>,
+                            limit: Option<u32>,
+                            include_zero_balances: Option<bool>| {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::AllAccounts { start_after, limit, include_zero_balances },
+            )
+            .unwrap();
+            from_json::<AllAccountsResponse>(&res).unwrap().accounts
+        };
+
+        assert_eq!(all_accounts(&deps, None, None, None), vec![addresses.owner.to_string()]);
+
+        let mut with_zero = all_accounts(&deps, None, None, Some(true));
+        with_zero.sort();
+        let mut expected = vec![addresses.owner.to_string(), addresses.user1.to_string()];
+        expected.sort();
+        assert_eq!(with_zero, expected);
+
+        // Pagination continues after the given address
+        let first = all_accounts(&deps, None, Some(1), Some(true));
+        let rest = all_accounts(&deps, first.last().cloned(), None, Some(true));
+        assert_eq!([first, rest].concat(), expected);
+    }
+
+    #[test]
+    fn test_migrate_legacy_amounts() {
+        let mut deps = mock_dependencies();
+        let owner = "owner".into_addr();
+        let spender = "spender".into_addr();
+
+        // Seed storage with the v0.1.0 layout, amounts as raw JSON numbers
+        v0_1::TOKEN_INFO
+            .save(
+                deps.as_mut().storage,
+                &v0_1::TokenInfo {
+                    name: "Test Token".to_string(),
+                    symbol: "TEST".to_string(),
+                    decimals: 6,
+                    circulating_supply: 1_000,
+                    max_supply: 2_000,
+                    owner: owner.clone(),
+                    fee_collector: "fee_collector".into_addr(),
+                    fee_rate: Decimal::percent(1),
+                },
+            )
+            .unwrap();
+        v0_1::BALANCES.save(deps.as_mut().storage, &owner, &1_000).unwrap();
+        let api = deps.api;
+        cw_ownable::initialize_owner(deps.as_mut().storage, &api, Some(owner.as_str())).unwrap();
+        v0_1::ALLOWANCES
+            .save(deps.as_mut().storage, (&owner, &spender), &500)
+            .unwrap();
+
+        // Instances deployed at v0.1.0 have no cw2 version stored
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(version.contract, CONTRACT_NAME);
+        assert_eq!(version.version, CONTRACT_VERSION);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap();
+        let token_info: TokenInfoResponse = from_json(&res).unwrap();
+        assert_eq!(token_info.total_supply, Uint128::new(1_000));
+        assert_eq!(query_balance(&deps, &owner), Uint128::new(1_000));
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AllSpenderAllowances {
+                spender: spender.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let by_spender: AllSpenderAllowancesResponse = from_json(&res).unwrap();
+        assert_eq!(by_spender.allowances.len(), 1);
+        assert_eq!(by_spender.allowances[0].allowance, Uint128::new(500));
+
+        // The owner keeps the minting rights it had before
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Minter {}).unwrap();
+        let minter: Option<MinterResponse> = from_json(&res).unwrap();
+        assert_eq!(minter.unwrap().minter, owner.to_string());
+    }
+
+    #[test]
+    fn test_migrate_rejects_foreign_contract_and_downgrade() {
+        let (mut deps, _) = setup_contract();
+
+        cw2::set_contract_version(deps.as_mut().storage, "crates.io:cw20-base", "1.0.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(matches!(err, ContractError::CannotMigrate { .. }));
+
+        cw2::set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "99.0.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(matches!(err, ContractError::CannotMigrateVersion { .. }));
+
+        // Re-running the current version is allowed
+        cw2::set_contract_version(deps.as_mut().storage, CONTRACT_NAME, CONTRACT_VERSION).unwrap();
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    }
+
+    #[test]
+    fn test_minter_role_and_cap() {
+        let (mut deps, addresses) = setup_contract();
+        let cap = INITIAL_SUPPLY + Uint128::new(1_000);
+
+        // Only the owner can hand out minting rights
+        let msg = ExecuteMsg::UpdateMinter {
+            new_minter: Some(addresses.user1.to_string()),
+            cap: Some(cap),
+        };
+        let user_info = message_info(&addresses.user1, &[]);
+        let err = execute(deps.as_mut(), mock_env(), user_info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Ownership(_)));
+        let owner_info = message_info(&addresses.owner, &[]);
+        execute_timelocked(&mut deps, &mock_env(), owner_info.clone(), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Minter {}).unwrap();
+        let minter: Option<MinterResponse> = from_json(&res).unwrap();
+        assert_eq!(
+            minter,
+            Some(MinterResponse { minter: addresses.user1.to_string(), cap: Some(cap) })
+        );
+
+        // The owner is no longer the minter
+        let mint = |amount: u128| ExecuteMsg::Mint {
+            recipient: addresses.user2.to_string(),
+            amount: Uint128::new(amount),
+        };
+        let err =
+            execute_timelocked(&mut deps, &mock_env(), owner_info.clone(), mint(1)).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let minter_info = message_info(&addresses.user1, &[]);
+        execute(deps.as_mut(), mock_env(), minter_info.clone(), mint(1_000)).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), minter_info.clone(), mint(1)).unwrap_err();
+        assert!(matches!(err, ContractError::CannotExceedCap { .. }));
+
+        // Caps are per minter, a granted minter is only bound by its own
+        let grant = ExecuteMsg::GrantRole {
+            role: Role::Minter,
+            address: addresses.user2.to_string(),
+        };
+        execute_timelocked(&mut deps, &mock_env(), owner_info.clone(), grant).unwrap();
+        let second_info = message_info(&addresses.user2, &[]);
+        execute(deps.as_mut(), mock_env(), second_info.clone(), mint(1_000)).unwrap();
+        let set_cap = ExecuteMsg::SetMinterCap {
+            minter: addresses.user2.to_string(),
+            cap: Some(cap + Uint128::new(1_000)),
+        };
+        execute_timelocked(&mut deps, &mock_env(), owner_info.clone(), set_cap).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), second_info.clone(), mint(1)).unwrap_err();
+        assert!(matches!(err, ContractError::CannotExceedCap { .. }));
+
+        // Replacing the cw20 minter leaves granted minters alone
+        let msg = ExecuteMsg::UpdateMinter { new_minter: None, cap: None };
+        execute_timelocked(&mut deps, &mock_env(), owner_info, msg).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), minter_info, mint(1)).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Minter {}).unwrap();
+        assert_eq!(from_json::<Option<MinterResponse>>(&res).unwrap(), None);
+        let msg = QueryMsg::HasRole {
+            role: Role::Minter,
+            address: addresses.user2.to_string(),
+        };
+        let res: HasRoleResponse =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(res.has_role);
+    }
+
+    #[test]
+    fn test_marketing_and_logo() {
+        let mut deps = mock_dependencies();
+        let owner = "owner".into_addr();
+        let marketing = "marketing".into_addr();
+
+        let msg = InstantiateMsg {
+            marketing: Some(InstantiateMarketingInfo {
+                project: Some("Test Project".to_string()),
+                description: None,
+                marketing: Some(marketing.to_string()),
+                logo: Some(Logo::Url("https://example.com/logo.png".to_string())),
+            }),
+            ..base_instantiate_msg(&owner, &"fee_collector".into_addr())
+        };
+        instantiate(deps.as_mut(), mock_env(), message_info(&owner, &[]), msg).unwrap();
+
+        // Only the marketing admin can change the branding
+        let update = ExecuteMsg::UpdateMarketing {
+            project: Some("".to_string()),
+            description: Some("A token".to_string()),
+            marketing: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), message_info(&owner, &[]), update.clone())
+            .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), message_info(&marketing, &[]), update).unwrap();
+
+        let bad_png = ExecuteMsg::UploadLogo(Logo::Embedded(EmbeddedLogo::Png(Binary::from(
+            b"not a png".as_slice(),
+        ))));
+        let err = execute(deps.as_mut(), mock_env(), message_info(&marketing, &[]), bad_png)
+            .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPngHeader {}));
+
+        let svg = Binary::from(b"<svg xmlns=\"http://www.w3.org/2000/svg\"></svg>".as_slice());
+        let upload = ExecuteMsg::UploadLogo(Logo::Embedded(EmbeddedLogo::Svg(svg.clone())));
+        execute(deps.as_mut(), mock_env(), message_info(&marketing, &[]), upload).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::MarketingInfo {}).unwrap();
+        let info: MarketingInfoResponse = from_json(&res).unwrap();
+        assert_eq!(
+            info,
+            MarketingInfoResponse {
+                project: None,
+                description: Some("A token".to_string()),
+                logo: Some(LogoInfo::Embedded),
+                marketing: Some(marketing),
+            }
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::DownloadLogo {}).unwrap();
+        let logo: DownloadLogoResponse = from_json(&res).unwrap();
+        assert_eq!(logo.mime_type, "image/svg+xml");
+        assert_eq!(logo.data, svg);
+    }
+
+    #[test]
+    fn test_fee_rate_in_basis_points() {
+        let mut deps = mock_dependencies();
+        let owner = "owner".into_addr();
+        let user = "user".into_addr();
+        let fee_collector = "fee_collector".into_addr();
+
+        let msg = |fee_rate: FeeRate, max_fee_rate: Option<Decimal>| InstantiateMsg {
+            name: "Stable".to_string(),
+            symbol: "STBL".to_string(),
+            fee_rate,
+            max_fee_rate,
+            ..base_instantiate_msg(&owner, &fee_collector)
+        };
+
+        // 5% is above the default 3% maximum, and a rate above 100% is never allowed
+        let info = message_info(&owner, &[]);
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg(FeeRate::Bps(500), None))
+            .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFees { .. }));
+        let err = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&owner, &[]),
+            msg(FeeRate::Bps(10), Some(Decimal::percent(101))),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFees { .. }));
+
+        // 10 bps given as a decimal string
+        let fee_rate: FeeRate = from_json(br#"{"decimal":"0.001"}"#).unwrap();
+        let info = message_info(&owner, &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg(fee_rate, None)).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let config: ConfigResponse = from_json(&res).unwrap();
+        assert_eq!(config.fee_rate, Decimal::bps(10));
+        assert_eq!(config.max_fee_rate, Decimal::percent(3));
+
+        let transfer = ExecuteMsg::Transfer {
+            recipient: user.to_string(),
+            amount: Uint128::new(100_000),
+        };
+        execute(deps.as_mut(), mock_env(), message_info(&owner, &[]), transfer).unwrap();
+        assert_eq!(query_balance(&deps, &user), Uint128::new(99_900));
+        assert_eq!(query_accrued_fees(&deps, &fee_collector), Uint128::new(100));
+    }
+
+    #[test]
+    fn test_fee_change_timelock() {
+        let (mut deps, addresses) = setup_contract();
+        let mut env = mock_env();
+        let new_collector = "treasury".into_addr();
+
+        let propose = ExecuteMsg::ProposeFeeChange {
+            fee_rate: FeeRate::Bps(200),
+            fee_recipients: single_recipient(&new_collector),
+            fee_tiers: vec![],
+        };
+        let user_info = message_info(&addresses.user1, &[]);
+        let err = execute(deps.as_mut(), env.clone(), user_info, propose.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let owner_info = message_info(&addresses.owner, &[]);
+        let res = execute(deps.as_mut(), env.clone(), owner_info.clone(), propose).unwrap();
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(res.events[0].ty, "fee_change_proposed");
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::PendingFeeChange {}).unwrap();
+        let pending: Option<PendingFeeChangeResponse> = from_json(&res).unwrap();
+        let pending = pending.unwrap();
+        assert_eq!(pending.fee_rate, Decimal::percent(2));
+        assert_eq!(pending.ready_at, Expiration::AtTime(env.block.time.plus_days(1)));
+
+        // The default delay is one day
+        env.block.time = env.block.time.plus_hours(23);
+        let apply = ExecuteMsg::ApplyFeeChange {};
+        let err =
+            execute(deps.as_mut(), env.clone(), owner_info.clone(), apply.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::FeeChangeNotReady { .. }));
+
+        env.block.time = env.block.time.plus_hours(1);
+        execute(deps.as_mut(), env.clone(), owner_info.clone(), apply.clone()).unwrap();
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap();
+        let config: ConfigResponse = from_json(&res).unwrap();
+        assert_eq!(config.fee_rate, Decimal::percent(2));
+        assert_eq!(config.fee_recipients, single_recipient(&new_collector));
+
+        let err = execute(deps.as_mut(), env, owner_info, apply).unwrap_err();
+        assert!(matches!(err, ContractError::NoPendingFeeChange {}));
+    }
+
+    #[test]
+    fn test_delays_are_bounded() {
+        let mut deps = mock_dependencies();
+        let owner = "owner".into_addr();
+        let fee_collector = "fee_collector".into_addr();
+        let info = message_info(&owner, &[]);
+
+        // Delays that would overflow the block time or height are rejected at instantiate
+        let msg = InstantiateMsg {
+            fee_change_delay: Some(Duration::Time(u64::MAX)),
+            ..base_instantiate_msg(&owner, &fee_collector)
+        };
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidDelay { .. }));
+        let msg = InstantiateMsg {
+            timelock_delay: Some(Duration::Height(u64::MAX)),
+            ..base_instantiate_msg(&owner, &fee_collector)
+        };
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidDelay { .. }));
+
+        let msg = InstantiateMsg {
+            fee_change_delay: Some(Duration::Time(365 * 24 * 60 * 60)),
+            timelock_delay: Some(Duration::Height(100_000)),
+            ..base_instantiate_msg(&owner, &fee_collector)
+        };
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    #[test]
+    fn test_fee_exemptions() {
+        let (mut deps, addresses) = setup_contract();
+        let owner_info = message_info(&addresses.owner, &[]);
+
+        let exempt = ExecuteMsg::AddFeeExemption {
+            address: addresses.user1.to_string(),
+            kind: ExemptionKind::Sender,
+        };
+        let user_info = message_info(&addresses.user1, &[]);
+        let err =
+            execute(deps.as_mut(), mock_env(), user_info.clone(), exempt.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), owner_info.clone(), exempt).unwrap();
+
+        // user1 is only exempt as a sender, so receiving still pays the fee
+        let transfer = |recipient: &Addr, amount: u128| ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::new(amount),
+        };
+        execute(deps.as_mut(), mock_env(), owner_info.clone(), transfer(&addresses.user1, 10_000))
+            .unwrap();
+        assert_eq!(query_balance(&deps, &addresses.user1), Uint128::new(9_900));
+
+        execute(deps.as_mut(), mock_env(), user_info, transfer(&addresses.user2, 5_000)).unwrap();
+        assert_eq!(query_balance(&deps, &addresses.user2), Uint128::new(5_000));
+
+        // Recipient exemptions apply to TransferFrom as well
+        let exempt = ExecuteMsg::AddFeeExemption {
+            address: addresses.user2.to_string(),
+            kind: ExemptionKind::Recipient,
+        };
+        execute(deps.as_mut(), mock_env(), owner_info.clone(), exempt).unwrap();
+        let allow = ExecuteMsg::IncreaseAllowance {
+            owner: addresses.owner.to_string(),
+            spender: addresses.user1.to_string(),
+            amount: Uint128::new(1_000),
+            expires: None,
+        };
+        execute(deps.as_mut(), mock_env(), owner_info.clone(), allow).unwrap();
+        let transfer_from = ExecuteMsg::TransferFrom {
+            owner: addresses.owner.to_string(),
+            recipient: addresses.user2.to_string(),
+            amount: Uint128::new(1_000),
+        };
+        let user_info = message_info(&addresses.user1, &[]);
+        execute(deps.as_mut(), mock_env(), user_info, transfer_from).unwrap();
+        assert_eq!(query_balance(&deps, &addresses.user2), Uint128::new(6_000));
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::FeeExemptions { start_after: None, limit: None },
+        )
+        .unwrap();
+        let exemptions: FeeExemptionsResponse = from_json(&res).unwrap();
+        assert_eq!(exemptions.exemptions.len(), 2);
+
+        let remove = ExecuteMsg::RemoveFeeExemption { address: addresses.user2.to_string() };
+        execute(deps.as_mut(), mock_env(), owner_info, remove).unwrap();
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::FeeExemptions { start_after: None, limit: None },
+        )
+        .unwrap();
+        let exemptions: FeeExemptionsResponse = from_json(&res).unwrap();
+        assert_eq!(
+            exemptions.exemptions,
+            vec![FeeExemptionInfo {
+                address: addresses.user1.to_string(),
+                kind: ExemptionKind::Sender,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_fee_split_across_recipients() {
+        let (mut deps, addresses) = setup_contract();
+        let mut env = mock_env();
+        let owner_info = message_info(&addresses.owner, &[]);
+        let treasury = "treasury".into_addr();
+        let staking = "staking".into_addr();
+
+        // Empty splits and zero weights are rejected
+        for fee_recipients in [
+            vec![],
+            vec![FeeRecipient { target: FeeTarget::Burn, weight: 0 }],
+        ] {
+            let msg = ExecuteMsg::ProposeFeeChange {
+                fee_rate: FeeRate::Bps(100),
+                fee_recipients,
+                fee_tiers: vec![],
+            };
+            let err = execute(deps.as_mut(), env.clone(), owner_info.clone(), msg).unwrap_err();
+            assert!(matches!(err, ContractError::InvalidFeeRecipients { .. }));
+        }
+
+        // 3:2:1 split between treasury, staking and burn
+        let msg = ExecuteMsg::ProposeFeeChange {
+            fee_rate: FeeRate::Bps(100),
+            fee_recipients: vec![
+                FeeRecipient { target: FeeTarget::Address(treasury.to_string()), weight: 3 },
+                FeeRecipient { target: FeeTarget::Address(staking.to_string()), weight: 2 },
+                FeeRecipient { target: FeeTarget::Burn, weight: 1 },
+            ],
+            fee_tiers: vec![],
+        };
+        execute(deps.as_mut(), env.clone(), owner_info.clone(), msg).unwrap();
+        env.block.time = env.block.time.plus_days(1);
+        let apply = ExecuteMsg::ApplyFeeChange {};
+        execute(deps.as_mut(), env.clone(), owner_info.clone(), apply).unwrap();
+
+        // A fee of 1_000 splits into 500, 333 and 166, the dust unit goes to the treasury
+        let transfer = ExecuteMsg::Transfer {
+            recipient: addresses.user1.to_string(),
+            amount: Uint128::new(100_000),
+        };
+        execute(deps.as_mut(), env.clone(), owner_info, transfer).unwrap();
+        assert_eq!(query_balance(&deps, &addresses.user1), Uint128::new(99_000));
+        assert_eq!(query_accrued_fees(&deps, &treasury), Uint128::new(501));
+        assert_eq!(query_accrued_fees(&deps, &staking), Uint128::new(333));
+
+        let res = query(deps.as_ref(), env, QueryMsg::TokenInfo {}).unwrap();
+        let token_info: TokenInfoResponse = from_json(&res).unwrap();
+        assert_eq!(token_info.total_supply, INITIAL_SUPPLY - Uint128::new(166));
+    }
+
+    #[test]
+    fn test_claim_accrued_fees() {
+        let (mut deps, addresses) = setup_contract();
+        let transfer = ExecuteMsg::Transfer {
+            recipient: addresses.user1.to_string(),
+            amount: Uint128::new(100_000),
+        };
+        execute(deps.as_mut(), mock_env(), message_info(&addresses.owner, &[]), transfer).unwrap();
+
+        // Fees stay in the accrual bucket until claimed but count towards the supply
+        assert_eq!(query_balance(&deps, &addresses.fee_collector), Uint128::zero());
+        assert_eq!(query_accrued_fees(&deps, &addresses.fee_collector), Uint128::new(1_000));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap();
+        let token_info: TokenInfoResponse = from_json(&res).unwrap();
+        assert_eq!(token_info.total_supply, INITIAL_SUPPLY);
+
+        // Accounts without accrued fees have nothing to claim
+        let claim = ExecuteMsg::ClaimFees { recipient: None };
+        let user_info = message_info(&addresses.user1, &[]);
+        let err = execute(deps.as_mut(), mock_env(), user_info, claim.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NoAccruedFees {}));
+
+        let collector_info = message_info(&addresses.fee_collector, &[]);
+        let claim_to = ExecuteMsg::ClaimFees { recipient: Some(addresses.user2.to_string()) };
+        let res = execute(deps.as_mut(), mock_env(), collector_info.clone(), claim_to).unwrap();
+        assert_eq!(res.attributes[0].value, "claim_fees");
+        assert_eq!(query_balance(&deps, &addresses.user2), Uint128::new(1_000));
+        assert_eq!(query_accrued_fees(&deps, &addresses.fee_collector), Uint128::zero());
+
+        let err = execute(deps.as_mut(), mock_env(), collector_info, claim).unwrap_err();
+        assert!(matches!(err, ContractError::NoAccruedFees {}));
+    }
+
+    #[test]
+    fn test_absolute_fee_bounds() {
+        let mut deps = mock_dependencies();
+        let owner = "owner".into_addr();
+        let user = "user".into_addr();
+        let fee_collector = "fee_collector".into_addr();
+        let msg = |min_fee: Option<Uint128>, max_fee: Option<Uint128>| InstantiateMsg {
+            min_fee,
+            max_fee,
+            ..base_instantiate_msg(&owner, &fee_collector)
+        };
+
+        let info = message_info(&owner, &[]);
+        let crossed = msg(Some(Uint128::new(500)), Some(Uint128::new(50)));
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), crossed).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFeeBounds { .. }));
+
+        let bounded = msg(Some(Uint128::new(50)), Some(Uint128::new(500)));
+        instantiate(deps.as_mut(), mock_env(), info.clone(), bounded).unwrap();
+
+        let transfer = |amount: u128| ExecuteMsg::Transfer {
+            recipient: user.to_string(),
+            amount: Uint128::new(amount),
+        };
+
+        // Less than the minimum fee cannot be transferred at all
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), transfer(49)).unwrap_err();
+        assert!(matches!(err, ContractError::BelowMinFee { .. }));
+
+        // 1% of 1_000 is raised to the minimum
+        execute(deps.as_mut(), mock_env(), info.clone(), transfer(1_000)).unwrap();
+        assert_eq!(query_balance(&deps, &user), Uint128::new(950));
+
+        // 1% of 100_000 is capped at the maximum
+        execute(deps.as_mut(), mock_env(), info, transfer(100_000)).unwrap();
+        assert_eq!(query_balance(&deps, &user), Uint128::new(100_450));
+        assert_eq!(query_accrued_fees(&deps, &fee_collector), Uint128::new(550));
+    }
+
+    #[test]
+    fn test_simulate_transfer_matches_execution() {
+        let (mut deps, addresses) = setup_contract();
+        let simulate = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, recipient: &Addr| {
+            let msg = QueryMsg::SimulateTransfer {
+                sender: addresses.owner.to_string(),
+                recipient: recipient.to_string(),
+                amount: Uint128::new(100_000),
+            };
+            from_json::<SimulateTransferResponse>(&query(deps.as_ref(), mock_env(), msg).unwrap())
+                .unwrap()
+        };
+
+        let preview = simulate(&deps, &addresses.user1);
+        assert_eq!(preview.net_amount, Uint128::new(99_000));
+        assert_eq!(preview.fee, Uint128::new(1_000));
+        assert_eq!(
+            preview.fee_breakdown,
+            vec![FeeShare {
+                target: FeeTarget::Address(addresses.fee_collector.to_string()),
+                amount: Uint128::new(1_000),
+            }]
+        );
+        assert!(!preview.exempt);
+
+        let transfer = ExecuteMsg::Transfer {
+            recipient: addresses.user1.to_string(),
+            amount: Uint128::new(100_000),
+        };
+        let owner_info = message_info(&addresses.owner, &[]);
+        execute(deps.as_mut(), mock_env(), owner_info.clone(), transfer).unwrap();
+        assert_eq!(query_balance(&deps, &addresses.user1), preview.net_amount);
+        assert_eq!(query_accrued_fees(&deps, &addresses.fee_collector), preview.fee);
+
+        // Exempt transfers report no fee at all
+        let exempt = ExecuteMsg::AddFeeExemption {
+            address: addresses.user2.to_string(),
+            kind: ExemptionKind::Recipient,
+        };
+        execute(deps.as_mut(), mock_env(), owner_info, exempt).unwrap();
+        let preview = simulate(&deps, &addresses.user2);
+        assert_eq!(preview.net_amount, Uint128::new(100_000));
+        assert_eq!(preview.fee, Uint128::zero());
+        assert!(preview.fee_breakdown.is_empty());
+        assert!(preview.exempt);
+    }
+
+    #[test]
+    fn test_fee_rounding_modes() {
+        let fee = |amount: u128, rounding: FeeRounding| {
+            calculate_fee(Uint128::new(amount), Decimal::percent(1), &[], rounding, None, None)
+                .unwrap()
+                .1
+                .u128()
+        };
+
+        // 1% of 150 is 1.5 and 1% of 250 is 2.5
+        assert_eq!((fee(150, FeeRounding::Floor), fee(250, FeeRounding::Floor)), (1, 2));
+        assert_eq!((fee(150, FeeRounding::Ceil), fee(250, FeeRounding::Ceil)), (2, 3));
+        assert_eq!((fee(150, FeeRounding::HalfEven), fee(250, FeeRounding::HalfEven)), (2, 2));
+        assert_eq!(fee(151, FeeRounding::HalfEven), 2);
+        assert_eq!(fee(249, FeeRounding::HalfEven), 2);
+    }
+
+    fn fee_rounding() -> impl Strategy<Value = FeeRounding> {
+        prop_oneof![
+            Just(FeeRounding::Floor),
+            Just(FeeRounding::Ceil),
+            Just(FeeRounding::HalfEven),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn prop_fee_rounding_preserves_amount(
+            amount in any::<u128>(),
+            bps in 0u64..=10_000,
+            rounding in fee_rounding(),
+        ) {
+            let amount = Uint128::new(amount);
+            let (net_amount, fee) =
+                calculate_fee(amount, Decimal::bps(bps), &[], rounding, None, None).unwrap();
+            prop_assert_eq!(net_amount + fee, amount);
+        }
+
+        #[test]
+        fn prop_bounded_fee_preserves_amount(
+            amount in any::<u128>(),
+            bps in 0u64..=10_000,
+            rounding in fee_rounding(),
+            min_fee in any::<u128>(),
+            spread in any::<u128>(),
+        ) {
+            let amount = Uint128::new(amount);
+            let min_fee = Uint128::new(min_fee);
+            let max_fee = min_fee.saturating_add(Uint128::new(spread));
+            let rate = Decimal::bps(bps);
+            match calculate_fee(amount, rate, &[], rounding, Some(min_fee), Some(max_fee)) {
+                Ok((net_amount, fee)) => {
+                    prop_assert_eq!(net_amount + fee, amount);
+                    prop_assert!(min_fee <= fee && fee <= max_fee);
+                }
+                Err(ContractError::BelowMinFee { .. }) => prop_assert!(amount < min_fee),
+                Err(err) => return Err(TestCaseError::fail(err.to_string())),
+            }
+        }
+    }
+
+    #[test]
+    fn test_tiered_fee_schedule() {
+        let (mut deps, addresses) = setup_contract();
+        let mut env = mock_env();
+        let owner_info = message_info(&addresses.owner, &[]);
+        let propose = |fee_tiers: Vec<FeeTier>| ExecuteMsg::ProposeFeeChange {
+            fee_rate: FeeRate::Bps(100),
+            fee_recipients: single_recipient(&addresses.fee_collector),
+            fee_tiers,
+        };
+        let tier = |threshold: u128, bps: u64| FeeTier {
+            threshold: Uint128::new(threshold),
+            rate: FeeRate::Bps(bps),
+        };
+
+        // Thresholds must increase and rates stay within the maximum
+        let unsorted = propose(vec![tier(100_000, 10), tier(10_000, 50)]);
+        let err = execute(deps.as_mut(), env.clone(), owner_info.clone(), unsorted).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFeeTiers { .. }));
+        let too_high = propose(vec![tier(10_000, 500)]);
+        let err = execute(deps.as_mut(), env.clone(), owner_info.clone(), too_high).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFees { .. }));
+
+        let tiers = propose(vec![tier(10_000, 50), tier(100_000, 10)]);
+        execute(deps.as_mut(), env.clone(), owner_info.clone(), tiers).unwrap();
+        env.block.time = env.block.time.plus_days(1);
+        let apply = ExecuteMsg::ApplyFeeChange {};
+        execute(deps.as_mut(), env.clone(), owner_info.clone(), apply).unwrap();
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::FeeSchedule {}).unwrap();
+        let schedule: FeeScheduleResponse = from_json(&res).unwrap();
+        assert_eq!(schedule.fee_rate, Decimal::percent(1));
+        assert_eq!(
+            schedule.fee_tiers,
+            vec![
+                FeeTier { threshold: Uint128::new(10_000), rate: Decimal::bps(50) },
+                FeeTier { threshold: Uint128::new(100_000), rate: Decimal::bps(10) },
+            ]
+        );
+
+        // 1% of 5_000, 0.5% of 50_000 and 0.1% of 200_000
+        for (amount, fee) in [(5_000u128, 50u128), (50_000, 250), (200_000, 200)] {
+            let msg = QueryMsg::SimulateTransfer {
+                sender: addresses.owner.to_string(),
+                recipient: addresses.user1.to_string(),
+                amount: Uint128::new(amount),
+            };
+            let preview: SimulateTransferResponse =
+                from_json(query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+            assert_eq!(preview.fee, Uint128::new(fee));
+
+            let transfer = ExecuteMsg::Transfer {
+                recipient: addresses.user1.to_string(),
+                amount: Uint128::new(amount),
+            };
+            execute(deps.as_mut(), env.clone(), owner_info.clone(), transfer).unwrap();
+        }
+        assert_eq!(query_accrued_fees(&deps, &addresses.fee_collector), Uint128::new(500));
+    }
+
+    #[test]
+    fn test_mint_and_burn_fees() {
+        let mut deps = mock_dependencies();
+        let owner = "owner".into_addr();
+        let user = "user".into_addr();
+        let fee_collector = "fee_collector".into_addr();
+        let msg = InstantiateMsg {
+            mint_fee_rate: Some(FeeRate::Bps(200)),
+            burn_fee_rate: Some(FeeRate::Bps(100)),
+            ..base_instantiate_msg(&owner, &fee_collector)
+        };
+        let info = message_info(&owner, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // The mint fee is minted to the collector on top of the recipient's share
+        let mint = ExecuteMsg::Mint {
+            recipient: user.to_string(),
+            amount: Uint128::new(100_000),
+        };
+        let res = execute_timelocked(&mut deps, &mock_env(), info.clone(), mint).unwrap();
+        assert!(res.attributes.iter().any(|attr| attr.key == "fee" && attr.value == "2000"));
+        assert_eq!(query_balance(&deps, &user), Uint128::new(98_000));
+        assert_eq!(query_accrued_fees(&deps, &fee_collector), Uint128::new(2_000));
+
+        // The burn fee is paid to the collector instead of being destroyed
+        let burn = ExecuteMsg::Burn { amount: Uint128::new(50_000) };
+        let res = execute(deps.as_mut(), mock_env(), message_info(&user, &[]), burn).unwrap();
+        assert!(res.attributes.iter().any(|attr| attr.key == "fee" && attr.value == "500"));
+        assert_eq!(query_balance(&deps, &user), Uint128::new(48_000));
+        assert_eq!(query_accrued_fees(&deps, &fee_collector), Uint128::new(2_500));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap();
+        let token_info: TokenInfoResponse = from_json(&res).unwrap();
+        assert_eq!(token_info.total_supply, INITIAL_SUPPLY + Uint128::new(100_000 - 49_500));
+    }
+
+    #[test]
+    fn test_pause_operations() {
+        let (mut deps, addresses) = setup_contract();
+        let owner_info = message_info(&addresses.owner, &[]);
+        let transfer = ExecuteMsg::Transfer {
+            recipient: addresses.user1.to_string(),
+            amount: Uint128::new(1_000),
+        };
+
+        // The owner is the default pauser
+        let pause = ExecuteMsg::Pause { operations: Some(vec![Operation::Mint]) };
+        let user_info = message_info(&addresses.user1, &[]);
+        let err = execute(deps.as_mut(), mock_env(), user_info, pause.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), owner_info.clone(), pause).unwrap();
+
+        // Only minting is halted
+        let mint = ExecuteMsg::Mint {
+            recipient: addresses.user1.to_string(),
+            amount: Uint128::new(1_000),
+        };
+        let err = execute_timelocked(&mut deps, &mock_env(), owner_info.clone(), mint).unwrap_err();
+        assert!(matches!(err, ContractError::Paused { operation: Operation::Mint }));
+        execute(deps.as_mut(), mock_env(), owner_info.clone(), transfer.clone()).unwrap();
+
+        let pause_all = ExecuteMsg::Pause { operations: None };
+        execute(deps.as_mut(), mock_env(), owner_info.clone(), pause_all).unwrap();
+        let err =
+            execute(deps.as_mut(), mock_env(), owner_info.clone(), transfer.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Paused { operation: Operation::Transfer }));
+        let burn = ExecuteMsg::Burn { amount: Uint128::new(1_000) };
+        let err = execute(deps.as_mut(), mock_env(), owner_info.clone(), burn).unwrap_err();
+        assert!(matches!(err, ContractError::Paused { operation: Operation::Burn }));
+
+        let unpause = ExecuteMsg::Unpause {
+            operations: Some(vec![Operation::Transfer, Operation::Burn, Operation::Allowance]),
+        };
+        execute(deps.as_mut(), mock_env(), owner_info.clone(), unpause).unwrap();
+        execute(deps.as_mut(), mock_env(), owner_info, transfer).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::PauseInfo {}).unwrap();
+        let info: PauseInfoResponse = from_json(&res).unwrap();
+        assert_eq!(info.paused, PausedOperations { mint: true, ..Default::default() });
+    }
+
+    #[test]
+    fn test_freeze_accounts() {
+        let (mut deps, addresses) = setup_contract();
+        let env = mock_env();
+        let owner_info = message_info(&addresses.owner, &[]);
+        let transfer = |recipient: &Addr| ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::new(10_000),
+        };
+        execute(deps.as_mut(), env.clone(), owner_info.clone(), transfer(&addresses.user1)).unwrap();
+
+        let freeze = ExecuteMsg::Freeze {
+            address: addresses.user1.to_string(),
+            reason: "sanctioned".to_string(),
+        };
+        let user_info = message_info(&addresses.user2, &[]);
+        let err = execute(deps.as_mut(), env.clone(), user_info, freeze.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), env.clone(), owner_info.clone(), freeze).unwrap();
+
+        // Funds can neither reach nor leave the frozen account
+        let err = execute(deps.as_mut(), env.clone(), owner_info.clone(), transfer(&addresses.user1))
+            .unwrap_err();
+        assert!(matches!(err, ContractError::AccountFrozen { .. }));
+        let frozen_info = message_info(&addresses.user1, &[]);
+        let err = execute(deps.as_mut(), env.clone(), frozen_info.clone(), transfer(&addresses.user2))
+            .unwrap_err();
+        assert!(matches!(err, ContractError::AccountFrozen { .. }));
+        // The preview refuses the transfer as well
+        let simulate = QueryMsg::SimulateTransfer {
+            sender: addresses.user1.to_string(),
+            recipient: addresses.user2.to_string(),
+            amount: Uint128::new(10_000),
+        };
+        assert!(query(deps.as_ref(), env.clone(), simulate).is_err());
+        let burn = ExecuteMsg::Burn { amount: Uint128::new(1_000) };
+        let err = execute(deps.as_mut(), env.clone(), frozen_info.clone(), burn).unwrap_err();
+        assert!(matches!(err, ContractError::AccountFrozen { .. }));
+        let mint = ExecuteMsg::Mint {
+            recipient: addresses.user1.to_string(),
+            amount: Uint128::new(1_000),
+        };
+        let err = execute_timelocked(&mut deps, &env, owner_info.clone(), mint).unwrap_err();
+        assert!(matches!(err, ContractError::AccountFrozen { .. }));
+
+        let allowance = ExecuteMsg::IncreaseAllowance {
+            owner: addresses.user1.to_string(),
+            spender: addresses.user2.to_string(),
+            amount: Uint128::new(1_000),
+            expires: None,
+        };
+        execute(deps.as_mut(), env.clone(), frozen_info.clone(), allowance).unwrap();
+        let transfer_from = ExecuteMsg::TransferFrom {
+            owner: addresses.user1.to_string(),
+            recipient: addresses.user2.to_string(),
+            amount: Uint128::new(1_000),
+        };
+        let spender_info = message_info(&addresses.user2, &[]);
+        let err = execute(deps.as_mut(), env.clone(), spender_info, transfer_from).unwrap_err();
+        assert!(matches!(err, ContractError::AccountFrozen { .. }));
+
+        // Nor can it spend what others approved
+        let allowance = ExecuteMsg::IncreaseAllowance {
+            owner: addresses.owner.to_string(),
+            spender: addresses.user1.to_string(),
+            amount: Uint128::new(1_000),
+            expires: None,
+        };
+        execute(deps.as_mut(), env.clone(), owner_info.clone(), allowance).unwrap();
+        let transfer_from = ExecuteMsg::TransferFrom {
+            owner: addresses.owner.to_string(),
+            recipient: addresses.user2.to_string(),
+            amount: Uint128::new(1_000),
+        };
+        let err = execute(deps.as_mut(), env.clone(), frozen_info.clone(), transfer_from)
+            .unwrap_err();
+        assert!(matches!(err, ContractError::AccountFrozen { .. }));
+        let burn_from = ExecuteMsg::BurnFrom {
+            owner: addresses.owner.to_string(),
+            amount: Uint128::new(1_000),
+        };
+        let err = execute(deps.as_mut(), env.clone(), frozen_info.clone(), burn_from).unwrap_err();
+        assert!(matches!(err, ContractError::AccountFrozen { .. }));
+
+        let msg = QueryMsg::FrozenAccounts { start_after: None, limit: None };
+        let res: FrozenAccountsResponse =
+            from_json(query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(
+            res.accounts,
+            vec![FrozenAccountInfo {
+                address: addresses.user1.to_string(),
+                reason: "sanctioned".to_string(),
+                frozen_at: env.block.time,
+            }]
+        );
+
+        let unfreeze = ExecuteMsg::Unfreeze { address: addresses.user1.to_string() };
+        execute(deps.as_mut(), env.clone(), owner_info, unfreeze).unwrap();
+        let transfer = ExecuteMsg::Transfer {
+            recipient: addresses.user2.to_string(),
+            amount: Uint128::new(5_000),
+        };
+        execute(deps.as_mut(), env, frozen_info, transfer).unwrap();
+        assert_eq!(query_balance(&deps, &addresses.user2), Uint128::new(4_950));
+    }
+
+    #[test]
+    fn test_roles() {
+        let (mut deps, addresses) = setup_contract();
+        let owner_info = message_info(&addresses.owner, &[]);
+        let manager_info = message_info(&addresses.user1, &[]);
+        let exempt = ExecuteMsg::AddFeeExemption {
+            address: addresses.user2.to_string(),
+            kind: ExemptionKind::Both,
+        };
+        let err = execute(deps.as_mut(), mock_env(), manager_info.clone(), exempt.clone())
+            .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        // Only the owner grants roles
+        let grant = ExecuteMsg::GrantRole {
+            role: Role::FeeManager,
+            address: addresses.user1.to_string(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), manager_info.clone(), grant.clone())
+            .unwrap_err();
+        assert!(matches!(err, ContractError::Ownership(_)));
+        execute_timelocked(&mut deps, &mock_env(), owner_info.clone(), grant).unwrap();
+        execute(deps.as_mut(), mock_env(), manager_info.clone(), exempt.clone()).unwrap();
+
+        let msg = QueryMsg::HasRole {
+            role: Role::FeeManager,
+            address: addresses.user1.to_string(),
+        };
+        let res: HasRoleResponse =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(res.has_role);
+        let msg = QueryMsg::RoleMembers { role: Role::FeeManager, start_after: None, limit: None };
+        let res: RoleMembersResponse =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        let mut expected = vec![addresses.owner.to_string(), addresses.user1.to_string()];
+        expected.sort();
+        assert_eq!(res.members, expected);
+
+        // Revoked and renounced roles stop working
+        let revoke = ExecuteMsg::RevokeRole {
+            role: Role::FeeManager,
+            address: addresses.user1.to_string(),
+        };
+        execute_timelocked(&mut deps, &mock_env(), owner_info.clone(), revoke).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), manager_info.clone(), exempt).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let renounce = ExecuteMsg::RenounceRole { role: Role::FeeManager };
+        let err = execute(deps.as_mut(), mock_env(), manager_info, renounce.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), owner_info, renounce).unwrap();
+        let msg = QueryMsg::RoleMembers { role: Role::FeeManager, start_after: None, limit: None };
+        let res: RoleMembersResponse =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(res.members.is_empty());
+    }
+
+    #[test]
+    fn test_timelocked_operations() {
+        let (mut deps, addresses) = setup_contract();
+        let mut env = mock_env();
+        let owner_info = message_info(&addresses.owner, &[]);
+        let user_info = message_info(&addresses.user1, &[]);
+        let mint = ExecuteMsg::Mint {
+            recipient: addresses.user1.to_string(),
+            amount: Uint128::new(1_000),
+        };
+        // The owner cannot act directly
+        let err =
+            execute(deps.as_mut(), env.clone(), owner_info.clone(), mint.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::TimelockRequired {}));
+        let grant = ExecuteMsg::GrantRole {
+            role: Role::Pauser,
+            address: addresses.user1.to_string(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), owner_info.clone(), grant).unwrap_err();
+        assert!(matches!(err, ContractError::TimelockRequired {}));
+
+        let schedule = ExecuteMsg::ScheduleOperation { msg: Box::new(mint.clone()) };
+        let err = execute(deps.as_mut(), env.clone(), user_info.clone(), schedule.clone())
+            .unwrap_err();
+        assert!(matches!(err, ContractError::Ownership(_)));
+        let res = execute(deps.as_mut(), env.clone(), owner_info.clone(), schedule).unwrap();
+        assert_eq!(res.events[0].ty, "operation_scheduled");
+
+        // Timelock messages cannot be nested
+        let nested = ExecuteMsg::ScheduleOperation {
+            msg: Box::new(ExecuteMsg::CancelOperation { id: 0 }),
+        };
+        let err = execute(deps.as_mut(), env.clone(), owner_info.clone(), nested).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidScheduledOperation {}));
+
+        let msg = QueryMsg::ScheduledOperations { start_after: None, limit: None };
+        let res: ScheduledOperationsResponse =
+            from_json(query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(res.operations.len(), 1);
+        assert_eq!(res.operations[0].msg, to_json_binary(&mint).unwrap());
+        assert_eq!(res.operations[0].ready_at, Expiration::AtTime(env.block.time.plus_days(2)));
+
+        // Anyone can run it, but only after the delay
+        let run = ExecuteMsg::ExecuteOperation { id: 0 };
+        let err = execute(deps.as_mut(), env.clone(), user_info.clone(), run.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::OperationNotReady { id: 0, .. }));
+        env.block.time = env.block.time.plus_days(2);
+        let paying_info = message_info(&addresses.user1, &coins(100, "ujuno"));
+        let err = execute(deps.as_mut(), env.clone(), paying_info, run.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::PaymentError(_)));
+        let res = execute(deps.as_mut(), env.clone(), user_info.clone(), run.clone()).unwrap();
+        assert_eq!(res.events[0].ty, "operation_executed");
+        assert_eq!(query_balance(&deps, &addresses.user1), Uint128::new(1_000));
+        let err = execute(deps.as_mut(), env.clone(), user_info.clone(), run).unwrap_err();
+        assert!(matches!(err, ContractError::OperationNotFound { id: 0 }));
+
+        // Cancelled operations never run
+        let schedule = ExecuteMsg::ScheduleOperation { msg: Box::new(mint) };
+        execute(deps.as_mut(), env.clone(), owner_info.clone(), schedule).unwrap();
+        let cancel = ExecuteMsg::CancelOperation { id: 1 };
+        let err = execute(deps.as_mut(), env.clone(), user_info.clone(), cancel.clone())
+            .unwrap_err();
+        assert!(matches!(err, ContractError::Ownership(_)));
+        execute(deps.as_mut(), env.clone(), owner_info, cancel).unwrap();
+        env.block.time = env.block.time.plus_days(2);
+        let run = ExecuteMsg::ExecuteOperation { id: 1 };
+        let err = execute(deps.as_mut(), env, user_info, run).unwrap_err();
+        assert!(matches!(err, ContractError::OperationNotFound { id: 1 }));
+        assert_eq!(query_balance(&deps, &addresses.user1), Uint128::new(1_000));
+    }
+
+    #[test]
+    fn test_rate_limits() {
+        let (mut deps, addresses) = setup_contract();
+        let mut env = mock_env();
+        let owner_info = message_info(&addresses.owner, &[]);
+        let transfer = |recipient: &Addr, amount: u128| ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::new(amount),
+        };
+        execute(deps.as_mut(), env.clone(), owner_info.clone(), transfer(&addresses.user1, 50_000))
+            .unwrap();
+
+        let global = RateLimit { amount: Uint128::new(10_000), window: 24 * 60 * 60 };
+        let set_global = ExecuteMsg::SetRateLimit { address: None, limit: Some(global.clone()) };
+        let user_info = message_info(&addresses.user1, &[]);
+        let err = execute(deps.as_mut(), env.clone(), user_info.clone(), set_global.clone())
+            .unwrap_err();
+        assert!(matches!(err, ContractError::Ownership(_)));
+        for window in [0, u64::MAX] {
+            let limit = RateLimit { amount: Uint128::new(10_000), window };
+            let msg = ExecuteMsg::SetRateLimit { address: None, limit: Some(limit) };
+            let err = execute(deps.as_mut(), env.clone(), owner_info.clone(), msg).unwrap_err();
+            assert!(matches!(err, ContractError::InvalidRateLimit {}));
+        }
+        // Setting a limit where there was none only tightens, so it takes effect right away
+        execute(deps.as_mut(), env.clone(), owner_info.clone(), set_global).unwrap();
+
+        execute(deps.as_mut(), env.clone(), user_info.clone(), transfer(&addresses.user2, 6_000))
+            .unwrap();
+        let res = query_rate_limit(&deps, &env, &addresses.user1);
+        assert_eq!(res.limit, Some(global));
+        assert_eq!(res.remaining, Some(Uint128::new(4_000)));
+        let transfer_over = transfer(&addresses.user2, 5_000);
+        let err =
+            execute(deps.as_mut(), env.clone(), user_info.clone(), transfer_over).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::RateLimitExceeded { remaining } if remaining == Uint128::new(4_000)
+        ));
+
+        // Spenders are held to the owner's limit
+        let allowance = ExecuteMsg::IncreaseAllowance {
+            owner: addresses.user1.to_string(),
+            spender: addresses.user2.to_string(),
+            amount: Uint128::new(10_000),
+            expires: None,
+        };
+        execute(deps.as_mut(), env.clone(), user_info.clone(), allowance).unwrap();
+        let transfer_from = ExecuteMsg::TransferFrom {
+            owner: addresses.user1.to_string(),
+            recipient: addresses.user2.to_string(),
+            amount: Uint128::new(5_000),
+        };
+        let spender_info = message_info(&addresses.user2, &[]);
+        let err = execute(deps.as_mut(), env.clone(), spender_info, transfer_from).unwrap_err();
+        assert!(matches!(err, ContractError::RateLimitExceeded { .. }));
+
+        // The window rolls, outflows stop counting a day after the end of their hourly bucket
+        env.block.time = env.block.time.plus_hours(23);
+        execute(deps.as_mut(), env.clone(), user_info.clone(), transfer(&addresses.user2, 4_000))
+            .unwrap();
+        env.block.time = env.block.time.plus_hours(1);
+        let res = query_rate_limit(&deps, &env, &addresses.user1);
+        assert_eq!(res.remaining, Some(Uint128::zero()));
+        env.block.time = env.block.time.plus_hours(1);
+        let res = query_rate_limit(&deps, &env, &addresses.user1);
+        assert_eq!(res.remaining, Some(Uint128::new(6_000)));
+
+        // An account limit takes precedence over the global one
+        let set_user = ExecuteMsg::SetRateLimit {
+            address: Some(addresses.user1.to_string()),
+            limit: Some(RateLimit { amount: Uint128::new(20_000), window: 60 * 60 }),
+        };
+        execute_timelocked(&mut deps, &env, owner_info.clone(), set_user).unwrap();
+        execute(deps.as_mut(), env.clone(), user_info.clone(), transfer(&addresses.user2, 15_000))
+            .unwrap();
+        let res = query_rate_limit(&deps, &env, &addresses.user1);
+        assert_eq!(res.remaining, Some(Uint128::new(5_000)));
+
+        let lift = ExecuteMsg::SetRateLimit { address: None, limit: None };
+        let err =
+            execute(deps.as_mut(), env.clone(), owner_info.clone(), lift.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::TimelockRequired {}));
+        execute_timelocked(&mut deps, &env, owner_info, lift).unwrap();
+        let res = query_rate_limit(&deps, &env, &addresses.owner);
+        assert_eq!(res, RateLimitResponse { limit: None, remaining: None });
+    }
+
+    #[test]
+    fn test_roles_follow_ownership() {
+        let (mut deps, addresses) = setup_contract();
+        let owner_info = message_info(&addresses.owner, &[]);
+        let new_owner_info = message_info(&addresses.user1, &[]);
+        let transfer = ExecuteMsg::UpdateOwnership(cw_ownable::Action::TransferOwnership {
+            new_owner: addresses.user1.to_string(),
+            expiry: None,
+        });
+        execute_timelocked(&mut deps, &mock_env(), owner_info.clone(), transfer).unwrap();
+        let accept = ExecuteMsg::UpdateOwnership(cw_ownable::Action::AcceptOwnership);
+        execute(deps.as_mut(), mock_env(), new_owner_info.clone(), accept).unwrap();
+
+        let mint = ExecuteMsg::Mint {
+            recipient: addresses.user2.to_string(),
+            amount: Uint128::new(1_000),
+        };
+        let err = execute(deps.as_mut(), mock_env(), owner_info, mint.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute_timelocked(&mut deps, &mock_env(), new_owner_info, mint).unwrap();
+
+        for role in Role::ALL {
+            let msg = QueryMsg::RoleMembers { role, start_after: None, limit: None };
+            let res: RoleMembersResponse =
+                from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            assert_eq!(res.members, vec![addresses.user1.to_string()]);
+        }
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Minter {}).unwrap();
+        let minter: Option<MinterResponse> = from_json(&res).unwrap();
+        assert_eq!(minter.unwrap().minter, addresses.user1.to_string());
+    }
+
+    #[test]
+    fn test_outflows_cannot_be_padded() {
+        let (mut deps, addresses) = setup_contract();
+        let mut env = mock_env();
+        let owner_info = message_info(&addresses.owner, &[]);
+        let limit = RateLimit { amount: Uint128::new(100_000), window: 24 * 60 * 60 };
+        let set_global = ExecuteMsg::SetRateLimit { address: None, limit: Some(limit) };
+        execute_timelocked(&mut deps, &env, owner_info.clone(), set_global).unwrap();
+
+        // A stranger cannot log zero outflows or zero allowances against the owner
+        let transfer_from = ExecuteMsg::TransferFrom {
+            owner: addresses.owner.to_string(),
+            recipient: addresses.user2.to_string(),
+            amount: Uint128::zero(),
+        };
+        let stranger_info = message_info(&addresses.user2, &[]);
+        let err = execute(deps.as_mut(), env.clone(), stranger_info, transfer_from).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidAmount {}));
+        let transfer = |amount: u128| ExecuteMsg::Transfer {
+            recipient: addresses.user1.to_string(),
+            amount: Uint128::new(amount),
+        };
+        let err = execute(deps.as_mut(), env.clone(), owner_info.clone(), transfer(0)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidAmount {}));
+        assert!(OUTFLOWS.may_load(&deps.storage, &addresses.owner).unwrap().is_none());
+        let msg = QueryMsg::AllAllowances {
+            owner: addresses.owner.to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let res: AllAllowancesResponse =
+            from_json(query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert!(res.allowances.is_empty());
+
+        // Outflows are kept in a fixed number of buckets however often the account sends
+        for _ in 0..100 {
+            execute(deps.as_mut(), env.clone(), owner_info.clone(), transfer(100)).unwrap();
+            env.block.time = env.block.time.plus_minutes(20);
+        }
+        let outflows = OUTFLOWS.load(&deps.storage, &addresses.owner).unwrap();
+        assert!(outflows.len() <= 24);
+        let res = query_rate_limit(&deps, &env, &addresses.owner);
+        assert!(res.remaining.unwrap() < Uint128::new(100_000));
+    }
+
+    #[test]
+    fn test_outflows_count_for_a_full_window() {
+        let (mut deps, addresses) = setup_contract();
+        let mut env = mock_env();
+        let owner_info = message_info(&addresses.owner, &[]);
+        let limit = RateLimit { amount: Uint128::new(10_000), window: 24 * 60 * 60 };
+        let set_global = ExecuteMsg::SetRateLimit { address: None, limit: Some(limit) };
+        execute(deps.as_mut(), env.clone(), owner_info.clone(), set_global).unwrap();
+
+        // Send the whole limit in the last second of an hourly bucket
+        let seconds = env.block.time.seconds();
+        env.block.time = Timestamp::from_seconds(seconds - seconds % 3_600 + 3_599);
+        let transfer = ExecuteMsg::Transfer {
+            recipient: addresses.user1.to_string(),
+            amount: Uint128::new(10_000),
+        };
+        execute(deps.as_mut(), env.clone(), owner_info.clone(), transfer.clone()).unwrap();
+
+        // 23h00m01s later the window has passed the start of the bucket but not the transfer
+        env.block.time = env.block.time.plus_seconds(23 * 60 * 60 + 1);
+        let err = execute(deps.as_mut(), env.clone(), owner_info.clone(), transfer.clone())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::RateLimitExceeded { remaining } if remaining.is_zero()
+        ));
+
+        // A full window after the end of the bucket the limit is available again
+        env.block.time = env.block.time.plus_hours(1);
+        execute(deps.as_mut(), env.clone(), owner_info, transfer).unwrap();
+    }